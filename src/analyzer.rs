@@ -0,0 +1,106 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+use crate::contracts;
+use crate::infra;
+use crate::manifest;
+use crate::readme;
+use crate::shredder::{Capability, Shredder};
+
+/// Which analyzer extracts a file's capabilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Analyzer {
+    Manifest,       // Package manifests: what the author builds with
+    Contract,       // OpenAPI, GraphQL, protobuf and SQL schemas
+    Readme,         // Project descriptions
+    Infrastructure, // Dockerfiles, Terraform, Kubernetes and CI workflows
+    Shredder,       // Source code, by AST
+}
+
+impl Analyzer {
+    /// The analyzer for a file, by its path alone. Anything that isn't a
+    /// manifest, contract, README or infrastructure file is shredded.
+    pub fn for_path(path: &Path) -> Self {
+        if manifest::is_manifest(path) {
+            Analyzer::Manifest
+        } else if contracts::is_contract(path) {
+            Analyzer::Contract
+        } else if readme::is_readme(path) {
+            Analyzer::Readme
+        } else if infra::is_infrastructure(path) {
+            Analyzer::Infrastructure
+        } else {
+            Analyzer::Shredder
+        }
+    }
+
+    /// What a file's capabilities are reported under: the manifest's source
+    /// extension (`Cargo.toml` -> "rs"), "md" for READMEs, the infrastructure
+    /// platform ("docker"), else the file's extension
+    pub fn language(self, path: &Path) -> &str {
+        let language = match self {
+            Analyzer::Manifest => manifest::manifest_extension(path),
+            Analyzer::Readme => Some("md"),
+            Analyzer::Infrastructure => infra::platform(path),
+            Analyzer::Contract | Analyzer::Shredder => path.extension().and_then(|e| e.to_str()),
+        };
+        language.unwrap_or("")
+    }
+
+    /// THE DISPATCH: Capabilities of a file's content, wherever it was read
+    /// from (the working tree, a commit's tree). `read_lockfile` gives the
+    /// content of a manifest's sibling lockfile by name, from the same place.
+    pub fn analyze(
+        self,
+        shredder: &mut Shredder,
+        path: &Path,
+        source: &str,
+        read_lockfile: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Vec<Capability>> {
+        match self {
+            Analyzer::Manifest => manifest::analyze_source(path, source, read_lockfile),
+            Analyzer::Contract => contracts::analyze_source(path, source),
+            Analyzer::Readme => Ok(readme::analyze_source(path, source)),
+            Analyzer::Infrastructure => infra::analyze_source(path, source),
+            Analyzer::Shredder => shredder.shred_source(path, source),
+        }
+    }
+
+    /// `analyze` for a file in the working tree
+    pub fn analyze_file(self, shredder: &mut Shredder, path: &Path) -> Result<Vec<Capability>> {
+        let source = fs::read_to_string(path)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        self.analyze(shredder, path, &source, &|lockfile| fs::read_to_string(dir.join(lockfile)).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatches_by_path() {
+        let mut shredder = Shredder::new().unwrap();
+        let analyze = |shredder: &mut Shredder, path: &str, source: &str| {
+            let path = Path::new(path);
+            let analyzer = Analyzer::for_path(path);
+            let lockfile = |name: &str| {
+                (name == "Cargo.lock").then(|| "[[package]]\nname = \"tokio\"\nversion = \"1.38.0\"\n".to_string())
+            };
+            let caps = analyzer.analyze(shredder, path, source, &lockfile).unwrap();
+            (analyzer, analyzer.language(path).to_string(), caps)
+        };
+
+        let (analyzer, language, caps) = analyze(&mut shredder, "api/Cargo.toml", "[dependencies]\ntokio = \"1\"\n");
+        assert_eq!((analyzer, language.as_str()), (Analyzer::Manifest, "rs"));
+        assert_eq!(caps[0].doc_comment.as_deref(), Some("Depends on tokio 1.38.0"));
+
+        let (analyzer, language, _) = analyze(&mut shredder, "Dockerfile", "FROM rust:1.78\n");
+        assert_eq!((analyzer, language.as_str()), (Analyzer::Infrastructure, "docker"));
+
+        let (analyzer, language, caps) = analyze(&mut shredder, "src/lib.py", "def handler():\n    pass\n");
+        assert_eq!((analyzer, language.as_str()), (Analyzer::Shredder, "py"));
+        assert_eq!(caps[0].name, "handler");
+    }
+}
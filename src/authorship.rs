@@ -1,9 +1,21 @@
 use anyhow::Result;
-use git2::{Blame, BlameOptions, Repository};
-use std::path::Path;
+use git2::{BlameOptions, DiffOptions, Mailmap, Oid, Patch, Repository, Signature, Sort};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::{OnceCell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// How many skipped commits in a row a line is traced back through
+const MAX_SKIPPED_DEPTH: usize = 5;
+
+/// Files a commit must touch to count as a mass reformat
+const REFORMAT_MIN_FILES: usize = 3;
 
 /// Code authorship information extracted from git blame
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorshipInfo {
     pub author_email: Option<String>,
     pub author_name: Option<String>,
@@ -11,49 +23,385 @@ pub struct AuthorshipInfo {
     pub authorship_confidence: f64, // 0.0 to 1.0
     pub is_self_authored: bool,
     pub contribution_percentage: f64, // 0.0 to 100.0
+    #[serde(default)]
+    pub contributors: Vec<Contributor>, // Most lines first
+    // Commits that changed the range over its history (author dates, RFC 3339)
+    #[serde(default)]
+    pub first_commit_at: Option<String>,
+    #[serde(default)]
+    pub last_commit_at: Option<String>,
+    #[serde(default)]
+    pub commit_count: usize,
+    #[serde(default)]
+    pub user_last_touched_at: Option<String>, // Newest such commit by (or co-authored by) the user
+}
+
+/// Someone credited with lines of a capability. A line is shared equally by
+/// its commit's author and `Co-authored-by:` co-authors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contributor {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub lines: f64,      // Credited lines (fractional when shared)
+    pub percentage: f64, // 0.0 to 100.0
+    pub is_user: bool,
+}
+
+/// One of the user's git identities (work, personal, noreply...); either
+/// part may be unknown
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Identity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Identity {
+    /// Parse `Name <email>`, a bare email or a bare name
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        match text.split_once('<') {
+            Some((name, email)) => Identity {
+                name: Some(name.trim().to_string()).filter(|n| !n.is_empty()),
+                email: Some(email.trim_end_matches('>').trim().to_string()).filter(|e| !e.is_empty()),
+            },
+            None if text.contains('@') => Identity {
+                name: None,
+                email: Some(text.to_string()),
+            },
+            None => Identity {
+                name: Some(text.to_string()).filter(|n| !n.is_empty()),
+                email: None,
+            },
+        }
+    }
+}
+
+/// Who last changed a line
+#[derive(Debug, Clone)]
+struct LineAuthor {
+    commit: Oid,
+    name: Option<String>,
+    email: Option<String>,
+}
+
+/// A hunk of a commit's diff to a file: (old start, old lines, new start,
+/// new lines), 1-based as in git
+type Hunk = (usize, usize, usize, usize);
+
+/// A commit that changed a file
+#[derive(Debug, Clone)]
+struct Touch {
+    time: i64,           // Author date, seconds since the epoch
+    hunks: Vec<Hunk>,    // Empty when the commit added the file
+    skipped: bool,       // Ignored or a reformat: traced through, not counted
+    by_user: bool,       // Authored or co-authored by the user
+}
+
+/// A blamed file: the author of each line, with skipped commits looked
+/// through, and the commits that changed it
+pub struct FileBlame {
+    lines: Vec<LineAuthor>, // Index 0 is line 1
+    history: Vec<Touch>,    // Newest first
+}
+
+/// Files each non-merge commit changed, for a file's history without a
+/// revwalk per file
+#[derive(Default)]
+struct ChangeIndex {
+    commits: Vec<Oid>, // Newest first
+    changes: HashMap<PathBuf, Vec<(usize, Oid, Oid)>>, // (commit, old blob, new blob), newest first
 }
 
-/// Analyze code authorship using git blame
+/// Analyze code authorship using git blame.
+///
+/// Commits that don't change logic are skipped, as `git blame` does with
+/// `--ignore-revs-file`: those in `.git-blame-ignore-revs` (or the file
+/// `blame.ignoreRevsFile` names), and detected bulk reformats. Their lines go
+/// to whoever wrote them before.
 pub struct AuthorshipAnalyzer {
     repo: Repository,
-    user_email: Option<String>,
-    user_name: Option<String>,
+    workdir: PathBuf, // Canonical, so relative and symlinked file paths resolve
+    user_emails: HashSet<String>,     // Normalized, see `normalize_email`
+    user_names: HashSet<String>,      // Normalized, see `normalize_name`
+    name_only_names: HashSet<String>, // Of identities given without an email
+    mailmap: Option<Mailmap>,
+    ignored_revs: HashSet<Oid>,
+    reformats: RefCell<HashMap<Oid, bool>>,              // See `is_reformat`
+    co_authors: RefCell<HashMap<Oid, Vec<Identity>>>,    // See `co_authors`
+    change_index: OnceCell<ChangeIndex>,                 // See `change_index`
 }
 
 impl AuthorshipAnalyzer {
-    /// Create a new authorship analyzer for a repository
-    pub fn new(repo_path: &Path, user_email: Option<String>, user_name: Option<String>) -> Result<Self> {
+    /// Create a new authorship analyzer for a repository. Lines count as the
+    /// user's when their author, after the repository's `.mailmap`, is exactly
+    /// one of `identities` (also mapped through `.mailmap`): by email, or by
+    /// name only when the author or the identity has no email.
+    pub fn new(repo_path: &Path, identities: &[Identity]) -> Result<Self> {
         let repo = Repository::open(repo_path)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| anyhow::anyhow!("Repository has no workdir"))?;
+        let workdir = workdir.canonicalize().unwrap_or_else(|_| workdir.to_path_buf());
+
+        let mailmap = repo.mailmap().ok();
+        let mut user_emails = HashSet::new();
+        let mut user_names = HashSet::new();
+        let mut name_only_names = HashSet::new();
+        for identity in identities {
+            user_emails.extend(identity.email.as_deref().map(normalize_email));
+            user_names.extend(identity.name.as_deref().map(normalize_name));
+            if identity.email.is_none() {
+                name_only_names.extend(identity.name.as_deref().map(normalize_name));
+            }
+
+            if let Some(canonical) = resolve_identity(mailmap.as_ref(), identity) {
+                user_emails.extend(canonical.email().map(normalize_email));
+                if canonical.name() != identity.email.as_deref() {
+                    user_names.extend(canonical.name().map(normalize_name));
+                }
+            }
+        }
+
+        let ignored_revs = ignored_revs(&repo, &workdir);
         Ok(Self {
             repo,
-            user_email,
-            user_name,
+            workdir,
+            user_emails,
+            user_names,
+            name_only_names,
+            mailmap,
+            ignored_revs,
+            reformats: RefCell::new(HashMap::new()),
+            co_authors: RefCell::new(HashMap::new()),
+            change_index: OnceCell::new(),
         })
     }
 
-    /// Analyze authorship for a specific file and line range
+    /// Blame a whole file once, for `analyze_blame` of each capability in it.
+    /// Authors are mapped through the repository's `.mailmap`.
+    pub fn blame(&self, file_path: &Path) -> Result<FileBlame> {
+        let path = self.relative_path(file_path);
+        let lines = self.blame_lines(&path, None, 0)?;
+        let history = self.history(&path);
+        Ok(FileBlame { lines, history })
+    }
+
+    /// Id of the blob committed at `file_path` in HEAD. Its blame stays the
+    /// same for as long as this does.
+    pub fn committed_blob(&self, file_path: &Path) -> Option<Oid> {
+        let tree = self.repo.head().ok()?.peel_to_tree().ok()?;
+        let entry = tree.get_path(&self.relative_path(file_path)).ok()?;
+        Some(entry.id())
+    }
+
+    /// `file_path` relative to the repository's workdir
+    fn relative_path(&self, file_path: &Path) -> PathBuf {
+        let absolute = file_path.canonicalize().unwrap_or_else(|_| file_path.to_path_buf());
+        absolute.strip_prefix(&self.workdir).unwrap_or(file_path).to_path_buf()
+    }
+
+    /// Line authors of `path` as of `newest` (HEAD if `None`). Lines from a
+    /// skipped commit are blamed again at its parent, matched up by a
+    /// whitespace-insensitive diff.
+    fn blame_lines(&self, path: &Path, newest: Option<Oid>, depth: usize) -> Result<Vec<LineAuthor>> {
+        let mut opts = BlameOptions::new();
+        opts.use_mailmap(true);
+        if let Some(newest) = newest {
+            opts.newest_commit(newest);
+        }
+        let blame = self.repo.blame_file(path, Some(&mut opts))?;
+
+        let mut lines = Vec::new();
+        let mut commit_lines = Vec::new(); // Each line's number in its commit's file
+        for hunk in blame.iter() {
+            let author = hunk.final_signature();
+            for offset in 0..hunk.lines_in_hunk() {
+                lines.push(LineAuthor {
+                    commit: hunk.final_commit_id(),
+                    name: author.name().map(|n| n.to_string()),
+                    email: author.email().map(|e| e.to_string()),
+                });
+                commit_lines.push(hunk.orig_start_line() + offset);
+            }
+        }
+        if depth >= MAX_SKIPPED_DEPTH {
+            return Ok(lines);
+        }
+
+        let blamed: Vec<Oid> = lines.iter().map(|line| line.commit).collect();
+        for commit in blamed.iter().copied().collect::<HashSet<Oid>>() {
+            if !self.ignored_revs.contains(&commit) && !self.is_reformat(commit) {
+                continue;
+            }
+            let parent = match self.repo.find_commit(commit).ok().and_then(|c| c.parent_id(0).ok()) {
+                Some(parent) => parent,
+                None => continue, // A root commit wrote its lines
+            };
+            let (before, after) = match (self.file_at(parent, path), self.file_at(commit, path)) {
+                (Some(before), Some(after)) => (before, after),
+                _ => continue,
+            };
+            let previous_lines = match self.blame_lines(path, Some(parent), depth + 1) {
+                Ok(previous_lines) => previous_lines,
+                Err(_) => continue,
+            };
+            let line_map = line_map(&before, &after)?;
+            for (index, line) in commit_lines.iter().enumerate() {
+                if blamed[index] != commit {
+                    continue;
+                }
+                let previous = line_map.get(line).and_then(|old| previous_lines.get(old - 1));
+                if let Some(previous) = previous {
+                    lines[index] = previous.clone();
+                }
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Commits that changed `path`, newest first, back to the one that added
+    /// it. Merges are left out: the commits they bring in are there instead.
+    fn history(&self, path: &Path) -> Vec<Touch> {
+        let index = self.change_index();
+        let mut history = Vec::new();
+        for &(i, old_blob, new_blob) in index.changes.get(path).into_iter().flatten() {
+            let commit = match self.repo.find_commit(index.commits[i]) {
+                Ok(commit) => commit,
+                Err(_) => continue,
+            };
+            let content = |blob: Oid| self.repo.find_blob(blob).ok().map(|blob| blob.content().to_vec());
+            let (before, after) = match (content(old_blob), content(new_blob)) {
+                (before, Some(after)) => (before, after),
+                (_, None) => continue, // Deleted (and later added again)
+            };
+            let hunks = match &before {
+                Some(before) => hunks(before, &after).unwrap_or_default(),
+                None => Vec::new(),
+            };
+            let skipped =
+                before.is_some() && (self.ignored_revs.contains(&commit.id()) || self.is_reformat(commit.id()));
+
+            let author = commit.author();
+            let author = self
+                .mailmap
+                .as_ref()
+                .and_then(|mailmap| mailmap.resolve_signature(&author).ok())
+                .unwrap_or(author);
+            let by_user = self.is_user_author(&author.email().map(str::to_string), &author.name().map(str::to_string))
+                || self.co_authors(commit.id()).iter().any(|p| self.is_user_author(&p.email, &p.name));
+            history.push(Touch {
+                time: author.when().seconds(),
+                hunks,
+                skipped,
+                by_user,
+            });
+            if before.is_none() {
+                break; // Added here
+            }
+        }
+        history
+    }
+
+    /// Files each non-merge commit reachable from HEAD changed, built on first
+    /// use with one tree diff per commit
+    fn change_index(&self) -> &ChangeIndex {
+        self.change_index.get_or_init(|| {
+            let mut index = ChangeIndex::default();
+            let mut revwalk = match self.repo.revwalk() {
+                Ok(revwalk) => revwalk,
+                Err(_) => return index,
+            };
+            if revwalk.push_head().is_err() || revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME).is_err() {
+                return index;
+            }
+            for oid in revwalk.flatten() {
+                let commit = match self.repo.find_commit(oid) {
+                    Ok(commit) if commit.parent_count() <= 1 => commit,
+                    _ => continue,
+                };
+                // A shallow clone's oldest commits have no parent to diff against
+                let before = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+                let diff = match commit.tree().and_then(|tree| self.repo.diff_tree_to_tree(before.as_ref(), Some(&tree), None)) {
+                    Ok(diff) => diff,
+                    Err(_) => continue,
+                };
+                let i = index.commits.len();
+                index.commits.push(oid);
+                for delta in diff.deltas() {
+                    if let Some(path) = delta.new_file().path() {
+                        let blobs = (i, delta.old_file().id(), delta.new_file().id());
+                        index.changes.entry(path.to_path_buf()).or_default().push(blobs);
+                    }
+                }
+            }
+            index
+        })
+    }
+
+    /// Content of `path` in a commit
+    fn file_at(&self, commit: Oid, path: &Path) -> Option<Vec<u8>> {
+        let tree = self.repo.find_commit(commit).ok()?.tree().ok()?;
+        let blob = tree.get_path(path).ok()?.to_object(&self.repo).ok()?.peel_to_blob().ok()?;
+        Some(blob.content().to_vec())
+    }
+
+    /// Heuristic for a formatting commit (`cargo fmt`, Prettier, black),
+    /// decided once per commit from its diff against its parent: only
+    /// whitespace changed; or it touches several files and at most a tenth of
+    /// the changed lines differ beyond whitespace; or it touches several
+    /// files, names a formatter, and removes about as many lines as it adds
+    fn is_reformat(&self, commit: Oid) -> bool {
+        if let Some(&reformat) = self.reformats.borrow().get(&commit) {
+            return reformat;
+        }
+        let reformat = self.detect_reformat(commit).unwrap_or(false);
+        self.reformats.borrow_mut().insert(commit, reformat);
+        reformat
+    }
+
+    fn detect_reformat(&self, commit: Oid) -> Option<bool> {
+        static FORMATTER_REGEX: OnceLock<Regex> = OnceLock::new();
+        let formatter_regex = FORMATTER_REGEX.get_or_init(|| {
+            Regex::new(
+                r"(?i)\b(?:fmt|rustfmt|gofmt|prettier|black|isort|autopep8|clang-format|eslint --fix|reformat\w*|format(?:ting)?)\b",
+            )
+            .expect("valid formatter regex")
+        });
+        let commit = self.repo.find_commit(commit).ok().filter(|commit| commit.parent_count() == 1)?;
+        let (before, after) = (commit.parent(0).ok()?.tree().ok()?, commit.tree().ok()?);
+        // (files, lines added, lines removed)
+        let changed_lines = |ignore_whitespace: bool| -> Option<(usize, usize, usize)> {
+            let mut opts = DiffOptions::new();
+            opts.ignore_whitespace(ignore_whitespace).context_lines(0);
+            let diff = self.repo.diff_tree_to_tree(Some(&before), Some(&after), Some(&mut opts)).ok()?;
+            let stats = diff.stats().ok()?;
+            Some((stats.files_changed(), stats.insertions(), stats.deletions()))
+        };
+        let (files_changed, additions, deletions) = changed_lines(false)?;
+        let (_, logic_additions, logic_deletions) = changed_lines(true)?;
+        let (changed, logic_changed) = (additions + deletions, logic_additions + logic_deletions);
+        if changed == 0 {
+            return Some(false);
+        }
+        if logic_changed == 0 {
+            return Some(true);
+        }
+
+        let many_files = files_changed >= REFORMAT_MIN_FILES;
+        let names_formatter = formatter_regex.is_match(commit.summary().unwrap_or(""));
+        let balanced = additions.abs_diff(deletions) * 5 <= changed;
+        Some(many_files && (logic_changed * 10 <= changed || (names_formatter && balanced)))
+    }
+
+    /// Analyze authorship for a specific file and line range (1-based, inclusive)
+    #[allow(dead_code)]
     pub fn analyze_file(
         &self,
         file_path: &Path,
         start_line: usize,
         end_line: usize,
     ) -> Result<AuthorshipInfo> {
-        // Get relative path from repo root
-        let repo_path = self.repo.workdir()
-            .ok_or_else(|| anyhow::anyhow!("Repository has no workdir"))?;
-        
-        let relative_path = file_path.strip_prefix(repo_path)
-            .or_else(|_| file_path.strip_prefix(repo_path.join(".git")))
-            .unwrap_or(file_path);
-
-        // Run git blame
-        let mut opts = BlameOptions::new();
-        opts.min_line(start_line + 1); // git blame is 1-indexed
-        opts.max_line(end_line + 1);
-        
-        let blame = self.repo.blame_file(relative_path, Some(&mut opts))?;
-
-        // Analyze blame results
+        let blame = self.blame(file_path)?;
         self.analyze_blame(&blame, start_line, end_line)
     }
 
@@ -63,28 +411,28 @@ impl AuthorshipAnalyzer {
         self.analyze_file(file_path, line, line)
     }
 
-    /// Analyze blame results to determine authorship
-    fn analyze_blame(
+    /// Authorship of a line range (1-based, inclusive), from a blame of the
+    /// whole file. Every line counts toward the contribution percentage,
+    /// shared between its author and co-authors.
+    pub fn analyze_blame(
         &self,
-        blame: &Blame,
+        blame: &FileBlame,
         start_line: usize,
         end_line: usize,
     ) -> Result<AuthorshipInfo> {
         let mut author_emails = Vec::new();
         let mut author_names = Vec::new();
         let mut commit_shas = Vec::new();
-        let mut user_lines = 0;
-        let total_lines = (end_line - start_line + 1) as f64;
+        let mut contributors: Vec<Contributor> = Vec::new();
+        let mut total_lines = 0;
 
         // Collect authorship info for each line
-        for i in start_line..=end_line {
-            if let Some(hunk) = blame.get_line(i + 1) {
-                let commit = hunk.final_commit_id();
-                let commit_obj = self.repo.find_commit(commit)?;
-                
-                let author = commit_obj.author();
-                let email = author.email().map(|e| e.to_string());
-                let name = author.name().map(|n| n.to_string());
+        for line in start_line.max(1)..=end_line {
+            if let Some(author) = blame.lines.get(line - 1) {
+                total_lines += 1;
+                let commit = author.commit;
+                let email = author.email.clone();
+                let name = author.name.clone();
 
                 if let Some(ref email) = email {
                     author_emails.push(email.clone());
@@ -94,25 +442,51 @@ impl AuthorshipAnalyzer {
                 }
                 commit_shas.push(commit.to_string());
 
-                // Check if this line was written by the user
-                let is_user_line = self.is_user_author(&email, &name);
-                if is_user_line {
-                    user_lines += 1;
+                // Split the line between everyone who wrote the commit
+                let mut people = vec![Identity { name, email }];
+                for co_author in self.co_authors(commit) {
+                    if !people.iter().any(|p| same_person(p, &co_author)) {
+                        people.push(co_author);
+                    }
+                }
+                let share = 1.0 / people.len() as f64;
+                for person in people {
+                    let known = contributors.iter_mut().find(|c| {
+                        same_person(&Identity { name: c.name.clone(), email: c.email.clone() }, &person)
+                    });
+                    match known {
+                        Some(contributor) => contributor.lines += share,
+                        None => contributors.push(Contributor {
+                            is_user: self.is_user_author(&person.email, &person.name),
+                            name: person.name,
+                            email: person.email,
+                            lines: share,
+                            percentage: 0.0,
+                        }),
+                    }
                 }
             }
         }
 
-        // Determine primary author (most common)
-        let primary_email = AuthorshipAnalyzer::most_common(&author_emails);
-        let primary_name = AuthorshipAnalyzer::most_common(&author_names);
-        let primary_commit = commit_shas.first().cloned();
+        if total_lines == 0 {
+            anyhow::bail!("No blamed lines in {}..={}", start_line, end_line);
+        }
+        for contributor in &mut contributors {
+            contributor.percentage = contributor.lines / total_lines as f64 * 100.0;
+        }
+        contributors.sort_by(|a, b| b.lines.total_cmp(&a.lines)); // Stable: first seen wins ties
+
+        // Determine primary author (most credited) and commit (most common)
+        let primary_email = contributors.first().and_then(|c| c.email.clone());
+        let primary_name = contributors.first().and_then(|c| c.name.clone());
+        let primary_commit = AuthorshipAnalyzer::most_common(&commit_shas);
 
         // Calculate contribution percentage
-        let contribution_percentage = if total_lines > 0.0 {
-            (user_lines as f64 / total_lines) * 100.0
-        } else {
-            100.0
-        };
+        let contribution_percentage: f64 = contributors
+            .iter()
+            .filter(|c| c.is_user)
+            .map(|c| c.percentage)
+            .sum();
 
         // Determine if self-authored (>= 80% by user)
         let is_self_authored = contribution_percentage >= 80.0;
@@ -122,6 +496,23 @@ impl AuthorshipAnalyzer {
         let name_consistency = AuthorshipAnalyzer::calculate_consistency(&author_names);
         let authorship_confidence = (email_consistency + name_consistency) / 2.0;
 
+        // Recency: every commit that changed the range, traced back through
+        // the file's history by where the range's lines were before each one
+        let mut touches = Vec::new();
+        let mut range = Some((start_line.max(1), end_line));
+        for touch in &blame.history {
+            let Some((start, end)) = range else { break };
+            let (touched, before) = match touch.hunks.is_empty() {
+                true => (true, None), // The commit added the file
+                false => trace_range(&touch.hunks, start, end),
+            };
+            if touched && !touch.skipped {
+                touches.push(touch);
+            }
+            range = before;
+        }
+        let times = touches.iter().map(|touch| touch.time);
+
         Ok(AuthorshipInfo {
             author_email: primary_email,
             author_name: primary_name,
@@ -129,28 +520,49 @@ impl AuthorshipAnalyzer {
             authorship_confidence,
             is_self_authored,
             contribution_percentage,
+            contributors,
+            first_commit_at: times.clone().min().and_then(rfc3339),
+            last_commit_at: times.max().and_then(rfc3339),
+            commit_count: touches.len(),
+            user_last_touched_at: touches.iter().filter(|t| t.by_user).map(|t| t.time).max().and_then(rfc3339),
         })
     }
 
-    /// Check if an author matches the user
-    fn is_user_author(&self, email: &Option<String>, name: &Option<String>) -> bool {
-        if let Some(ref user_email) = self.user_email {
-            if let Some(ref author_email) = email {
-                if author_email == user_email || author_email.contains(user_email) {
-                    return true;
-                }
-            }
+    /// `Co-authored-by:` trailers of a commit, mapped through `.mailmap`
+    fn co_authors(&self, commit: Oid) -> Vec<Identity> {
+        if let Some(co_authors) = self.co_authors.borrow().get(&commit) {
+            return co_authors.clone();
         }
+        let message = self
+            .repo
+            .find_commit(commit)
+            .ok()
+            .and_then(|commit| commit.message().map(|m| m.to_string()))
+            .unwrap_or_default();
+        let co_authors: Vec<Identity> = co_author_trailers(&message)
+            .into_iter()
+            .map(|identity| match resolve_identity(self.mailmap.as_ref(), &identity) {
+                Some(canonical) => Identity {
+                    name: canonical.name().map(|n| n.to_string()).or(identity.name),
+                    email: canonical.email().map(|e| e.to_string()),
+                },
+                None => identity,
+            })
+            .collect();
+        self.co_authors.borrow_mut().insert(commit, co_authors.clone());
+        co_authors
+    }
 
-        if let Some(ref user_name) = self.user_name {
-            if let Some(ref author_name) = name {
-                if author_name == user_name || author_name.contains(user_name) {
-                    return true;
-                }
-            }
+    /// Check if an author is one of the user's identities: the same email, or
+    /// the same name when the author has no email or the identity gave none.
+    /// A namesake with another email is not the user.
+    fn is_user_author(&self, email: &Option<String>, name: &Option<String>) -> bool {
+        let name = name.as_deref().map(normalize_name);
+        let name_in = |names: &HashSet<String>| name.as_ref().map(|n| names.contains(n)).unwrap_or(false);
+        match email.as_deref().filter(|e| !e.is_empty()) {
+            Some(email) => self.user_emails.contains(&normalize_email(email)) || name_in(&self.name_only_names),
+            None => name_in(&self.user_names),
         }
-
-        false
     }
 
     /// Find most common value in a vector
@@ -186,12 +598,420 @@ impl AuthorshipAnalyzer {
     }
 }
 
+/// Commits listed in `.git-blame-ignore-revs` and the `blame.ignoreRevsFile`
+/// of the repository's config (full or abbreviated ids, `#` comments)
+fn ignored_revs(repo: &Repository, workdir: &Path) -> HashSet<Oid> {
+    let mut files = vec![workdir.join(".git-blame-ignore-revs")];
+    if let Ok(file) = repo.config().and_then(|config| config.get_path("blame.ignoreRevsFile")) {
+        files.push(workdir.join(file));
+    }
+    files
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or("").trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|rev| !rev.is_empty())
+        .filter_map(|rev| repo.revparse_single(&rev).ok()?.peel_to_commit().ok())
+        .map(|commit| commit.id())
+        .collect()
+}
+
+/// For each line of `after` (1-based), the line of `before` it came from.
+/// Changed lines pair with a removed line that reads the same but for
+/// formatting, else with the removed line at the same position in the hunk.
+fn line_map(before: &[u8], after: &[u8]) -> Result<HashMap<usize, usize>> {
+    let mut opts = DiffOptions::new();
+    opts.ignore_whitespace(true).context_lines(0);
+    let patch = Patch::from_buffers(before, None, after, None, Some(&mut opts))?;
+
+    let mut map = HashMap::new();
+    let (mut old_line, mut new_line) = (1, 1);
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index)?;
+        // A range of zero lines starts after the line it names
+        let old_start = hunk.old_start() as usize + usize::from(hunk.old_lines() == 0);
+        let new_start = hunk.new_start() as usize + usize::from(hunk.new_lines() == 0);
+        while new_line < new_start {
+            map.insert(new_line, old_line);
+            new_line += 1;
+            old_line += 1;
+        }
+
+        let mut removed: Vec<(usize, String)> = Vec::new();
+        let mut added: Vec<(usize, String)> = Vec::new();
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(hunk_index, line_index)?;
+            let text = formatting_insensitive(&String::from_utf8_lossy(line.content()));
+            match (line.origin(), line.old_lineno(), line.new_lineno()) {
+                ('-', Some(old), _) => removed.push((old as usize, text)),
+                ('+', _, Some(new)) => added.push((new as usize, text)),
+                _ => {}
+            }
+        }
+        let mut unmatched = Vec::new();
+        for (new, text) in added {
+            match removed.iter().position(|(_, old_text)| *old_text == text) {
+                Some(position) => {
+                    map.insert(new, removed.remove(position).0);
+                }
+                None => unmatched.push(new),
+            }
+        }
+        for (new, (old, _)) in unmatched.into_iter().zip(removed) {
+            map.insert(new, old);
+        }
+
+        old_line = old_start + hunk.old_lines() as usize;
+        new_line = new_start + hunk.new_lines() as usize;
+    }
+    let after_lines = after.split(|b| *b == b'\n').count();
+    while new_line <= after_lines {
+        map.insert(new_line, old_line);
+        new_line += 1;
+        old_line += 1;
+    }
+    Ok(map)
+}
+
+/// A line with what formatters change removed: whitespace, quote style and
+/// trailing separators
+fn formatting_insensitive(line: &str) -> String {
+    let text: String = line
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == '\'' { '"' } else { c })
+        .collect();
+    text.trim_end_matches([',', ';']).to_string()
+}
+
+/// Hunks of a change to a file, without context lines
+fn hunks(before: &[u8], after: &[u8]) -> Result<Vec<Hunk>> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(0);
+    let patch = Patch::from_buffers(before, None, after, None, Some(&mut opts))?;
+    (0..patch.num_hunks())
+        .map(|i| {
+            let (hunk, _) = patch.hunk(i)?;
+            Ok((
+                hunk.old_start() as usize,
+                hunk.old_lines() as usize,
+                hunk.new_start() as usize,
+                hunk.new_lines() as usize,
+            ))
+        })
+        .collect()
+}
+
+/// Whether a commit's hunks change lines `start..=end` of the file it left
+/// behind, and where those lines were before it: `None` once the commit
+/// added all of them
+fn trace_range(hunks: &[Hunk], start: usize, end: usize) -> (bool, Option<(usize, usize)>) {
+    let touched = hunks.iter().any(|&(_, _, new_start, new_lines)| match new_lines {
+        0 => start <= new_start && new_start < end, // Lines removed from inside the range
+        _ => new_start <= end && start < new_start + new_lines,
+    });
+
+    // Lines the commit added outright weren't anywhere before it
+    let added = |line: usize| {
+        hunks.iter().find(|&&(_, old_lines, new_start, new_lines)| {
+            old_lines == 0 && new_start <= line && line < new_start + new_lines
+        })
+    };
+    let (mut start, mut end) = (start, end);
+    if let Some(&(_, _, new_start, new_lines)) = added(start) {
+        start = new_start + new_lines;
+    }
+    if let Some(&(_, _, new_start, _)) = added(end) {
+        end = new_start - 1;
+    }
+    if start > end {
+        return (touched, None);
+    }
+
+    let before = |line: usize, first: bool| {
+        let mut shift = 0isize;
+        for &(old_start, old_lines, new_start, new_lines) in hunks {
+            if new_lines > 0 && new_start <= line && line < new_start + new_lines {
+                return if first { old_start } else { old_start + old_lines - 1 };
+            }
+            let past = match new_lines {
+                0 => line > new_start,
+                _ => line >= new_start + new_lines,
+            };
+            if !past {
+                break;
+            }
+            shift += old_lines as isize - new_lines as isize;
+        }
+        line.saturating_add_signed(shift)
+    };
+    (touched, Some((before(start, true), before(end, false))))
+}
+
+fn rfc3339(seconds: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(seconds, 0).map(|time| time.to_rfc3339())
+}
+
+/// The canonical signature `.mailmap` gives an identity (mailmaps key on email)
+fn resolve_identity(mailmap: Option<&Mailmap>, identity: &Identity) -> Option<Signature<'static>> {
+    let email = identity.email.as_deref()?;
+    let signature = Signature::now(identity.name.as_deref().unwrap_or(email), email).ok()?;
+    mailmap?.resolve_signature(&signature).ok()
+}
+
+/// Identities in a commit message's `Co-authored-by:` trailers
+fn co_author_trailers(message: &str) -> Vec<Identity> {
+    static TRAILER_REGEX: OnceLock<Regex> = OnceLock::new();
+    let trailer_regex = TRAILER_REGEX
+        .get_or_init(|| Regex::new(r"(?im)^[ \t]*co-authored-by:[ \t]*(.+?)[ \t]*$").expect("valid trailer regex"));
+    trailer_regex
+        .captures_iter(message)
+        .map(|caps| Identity::parse(&caps[1]))
+        .filter(|identity| identity.name.is_some() || identity.email.is_some())
+        .collect()
+}
+
+/// Whether two identities are the same person: same email, or same name when
+/// either email is unknown
+fn same_person(a: &Identity, b: &Identity) -> bool {
+    match (&a.email, &b.email) {
+        (Some(x), Some(y)) => normalize_email(x) == normalize_email(y),
+        _ => match (&a.name, &b.name) {
+            (Some(x), Some(y)) => normalize_name(x) == normalize_name(y),
+            _ => false,
+        },
+    }
+}
+
+/// Email compared case-insensitively; GitHub noreply addresses with and
+/// without the numeric id (`123+octo@users.noreply.github.com`) are the same
+fn normalize_email(email: &str) -> String {
+    let email = email.trim().trim_start_matches('<').trim_end_matches('>').to_lowercase();
+    match email.split_once('@') {
+        Some((local, "users.noreply.github.com")) => {
+            let login = match local.split_once('+') {
+                Some((id, login)) if id.chars().all(|c| c.is_ascii_digit()) => login,
+                _ => local,
+            };
+            format!("{}@users.noreply.github.com", login)
+        }
+        _ => email,
+    }
+}
+
+/// Name compared case-insensitively, with runs of whitespace collapsed
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
-    use tempfile::TempDir;
 
-    // Note: These tests would require making the helper functions public
-    // For now, they're tested indirectly through the analyze_file method
+    #[test]
+    fn test_attributes_full_line_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let commit = |content: &str, author: &str, time: i64| {
+            std::fs::write(dir.path().join("lib.py"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("lib.py")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let email = format!("{}@example.com", author.to_lowercase());
+            let signature = git2::Signature::new(author, &email, &git2::Time::new(time, 0)).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, author, &tree, &parents).unwrap();
+        };
+        // Ann writes `a` in 2020 and Cy edits it in 2021; Bob rewrites most of
+        // its body and adds `b` in 2023; Cy edits only `b` in 2024
+        commit("def a():\n    x = 1\n    y = 2\n    z = 3\n    return x\n", "Ann", 1_600_000_000);
+        commit("def a():\n    x = 1\n    y = 5\n    z = 3\n    return x\n", "Cy", 1_630_000_000);
+        commit(
+            "def a():\n    x = 10\n    y = 20\n    z = 30\n    return x\n\ndef b():\n    pass\n",
+            "Bob",
+            1_700_000_000,
+        );
+        commit(
+            "def a():\n    x = 10\n    y = 20\n    z = 30\n    return x\n\ndef b():\n    return 1\n",
+            "Cy",
+            1_720_000_000,
+        );
+        std::fs::write(dir.path().join("scratch.py"), "x = 1\n").unwrap();
+
+        let analyzer = AuthorshipAnalyzer::new(dir.path(), &[Identity::parse("ann@example.com")]).unwrap();
+        let blame = analyzer.blame(&dir.path().join("lib.py")).unwrap();
+
+        // `a` starts with Ann's line, but Bob wrote 3 of its 5 lines
+        let a = analyzer.analyze_blame(&blame, 1, 5).unwrap();
+        assert_eq!(a.author_name.as_deref(), Some("Bob"));
+        assert!((a.contribution_percentage - 40.0).abs() < 0.001);
+        assert!(!a.is_self_authored);
+        assert_eq!(a.first_commit_at.as_deref(), Some("2020-09-13T12:26:40+00:00"));
+        assert_eq!(a.last_commit_at.as_deref(), Some("2023-11-14T22:13:20+00:00"));
+        assert_eq!(a.commit_count, 3); // Cy's line is gone, but Cy changed it
+        assert_eq!(a.user_last_touched_at.as_deref(), Some("2020-09-13T12:26:40+00:00"));
+
+        let first_line = analyzer.analyze_blame(&blame, 1, 1).unwrap();
+        assert_eq!(first_line.author_name.as_deref(), Some("Ann"));
+        assert!(first_line.is_self_authored);
+
+        let b = analyzer.analyze_blame(&blame, 7, 8).unwrap();
+        assert_eq!(b.contribution_percentage, 0.0);
+        assert_eq!(b.first_commit_at.as_deref(), Some("2023-11-14T22:13:20+00:00"));
+        assert_eq!(b.commit_count, 2);
+        assert_eq!(b.user_last_touched_at, None);
+        assert!(analyzer.analyze_blame(&blame, 20, 30).is_err()); // Past the end
+        assert!(analyzer.blame(&dir.path().join("scratch.py")).is_err()); // Untracked
+    }
+
+    #[test]
+    fn test_matches_identities_through_mailmap() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let lines = [
+            ("Alice", "alice@work.example"),
+            ("alice", "alice@home.example"),
+            ("Alice", "1234+alice@users.noreply.github.com"),
+            ("Mallory", "alice@work.example.evil"),
+            ("Alice", "other@corp.example"), // A namesake
+        ];
+        let mut content = String::new();
+        for (name, email) in lines {
+            content.push_str(&format!("# by {}\n", email));
+            std::fs::write(dir.path().join("lib.py"), &content).unwrap();
+            std::fs::write(dir.path().join(".mailmap"), "Alice <alice@work.example> <alice@home.example>\n").unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("lib.py")).unwrap();
+            index.add_path(Path::new(".mailmap")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now(name, email).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, email, &tree, &parents).unwrap();
+        }
+
+        let identities = [
+            Identity::parse("Alice <alice@WORK.example>"),
+            Identity::parse("alice@users.noreply.github.com"),
+        ];
+        let analyzer = AuthorshipAnalyzer::new(dir.path(), &identities).unwrap();
+        let blame = analyzer.blame(&dir.path().join("lib.py")).unwrap();
+        let mine: Vec<bool> = (1..=5)
+            .map(|line| analyzer.analyze_blame(&blame, line, line).unwrap().is_self_authored)
+            .collect();
+        assert_eq!(mine, vec![true, true, true, false, false]);
+
+        // Without an email, an identity matches by name
+        let by_name = AuthorshipAnalyzer::new(dir.path(), &[Identity::parse("Mallory")]).unwrap();
+        assert!(by_name.analyze_blame(&blame, 4, 4).unwrap().is_self_authored);
+
+        // The home address is reported as its canonical identity
+        let home = analyzer.analyze_blame(&blame, 2, 2).unwrap();
+        assert_eq!(home.author_email.as_deref(), Some("alice@work.example"));
+
+        assert_eq!(
+            Identity::parse("Alice Smith <alice@work.example>"),
+            Identity {
+                name: Some("Alice Smith".to_string()),
+                email: Some("alice@work.example".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_looks_through_formatting_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let commit = |files: &[(&str, &str)], author: &str, message: &str| {
+            let mut index = repo.index().unwrap();
+            for (path, content) in files {
+                std::fs::write(dir.path().join(path), content).unwrap();
+                index.add_path(Path::new(path)).unwrap();
+            }
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now(author, &format!("{}@example.com", author.to_lowercase())).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap()
+        };
+        commit(&[("app.js", "function greet(name) {\n  const msg = 'hi ' + name\n  return msg\n}\n")], "Ann", "Add greet");
+        // Re-indentation only
+        commit(&[("app.js", "function greet(name) {\n    const msg = 'hi ' + name\n    return msg\n}\n")], "Bob", "Tidy");
+        // Quote style, listed in .git-blame-ignore-revs below
+        let quotes = commit(
+            &[("app.js", "function greet(name) {\n    const msg = \"hi \" + name;\n    return msg;\n}\n")],
+            "Bob",
+            "Switch to double quotes",
+        );
+        commit(
+            &[
+                ("app.js", "function greet(name) {\n    const msg = \"hi \" + name;\n    log(msg);\n    return msg;\n}\n"),
+                (".git-blame-ignore-revs", &format!("# Style\n{}\n", &quotes.to_string()[..10])),
+            ],
+            "Carol",
+            "Log greetings",
+        );
+
+        let analyzer = AuthorshipAnalyzer::new(dir.path(), &[Identity::parse("ann@example.com")]).unwrap();
+        let blame = analyzer.blame(&dir.path().join("app.js")).unwrap();
+        let authors: Vec<_> = (1..=5)
+            .map(|line| analyzer.analyze_blame(&blame, line, line).unwrap().author_name.unwrap())
+            .collect();
+        assert_eq!(authors, vec!["Ann", "Ann", "Carol", "Ann", "Ann"]);
+        assert!((analyzer.analyze_blame(&blame, 1, 5).unwrap().contribution_percentage - 80.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_splits_credit_across_co_authors() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let commit = |content: &str, message: &str| {
+            std::fs::write(dir.path().join("lib.py"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("lib.py")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("Ann", "ann@example.com").unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap();
+        };
+        commit("def a():\n    return 1\n", "Add a");
+        // Paired with Bob (and Ann herself, who counts once) on two more lines
+        commit(
+            "def a():\n    x = 1\n    y = 2\n    return 1\n",
+            "Pair on a\n\nco-authored-by: Bob <BOB@example.com>\nCo-Authored-By: Ann <ann@example.com>\n",
+        );
+
+        let analyzer = AuthorshipAnalyzer::new(dir.path(), &[Identity::parse("bob@example.com")]).unwrap();
+        let blame = analyzer.blame(&dir.path().join("lib.py")).unwrap();
+        let info = analyzer.analyze_blame(&blame, 1, 4).unwrap();
+
+        let shares: Vec<_> = info.contributors.iter().map(|c| (c.name.as_deref(), c.lines, c.is_user)).collect();
+        assert_eq!(shares, vec![(Some("Ann"), 3.0, false), (Some("Bob"), 1.0, true)]);
+        assert_eq!(info.author_name.as_deref(), Some("Ann"));
+        assert!((info.contribution_percentage - 25.0).abs() < 0.001);
+        assert_eq!(
+            co_author_trailers("Fix\n\nCo-authored-by: Cy <cy@example.com>\nSigned-off-by: Di <di@example.com>"),
+            vec![Identity::parse("Cy <cy@example.com>")]
+        );
+    }
+
+    #[test]
+    fn test_most_common() {
+        let items = vec!["a", "b", "a"];
+        assert_eq!(AuthorshipAnalyzer::most_common(&items), Some("a"));
+        assert_eq!(AuthorshipAnalyzer::most_common::<&str>(&[]), None);
+    }
+
+    #[test]
+    fn test_calculate_consistency() {
+        let items = vec!["a", "b", "a", "a"];
+        assert!((AuthorshipAnalyzer::calculate_consistency(&items) - 0.75).abs() < 0.001);
+        assert_eq!(AuthorshipAnalyzer::calculate_consistency::<&str>(&[]), 0.0);
+    }
 }
@@ -2,16 +2,19 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
+use std::path::{Path, PathBuf};
 
-use crate::vectorizer::EmbeddedCapability;
+use crate::scan_manifest::content_hash;
 
 /// THE CACHE: Stores embeddings to avoid re-computation
+///
+/// Stored at `.echeo/embeddings_cache.json`, keyed by the model and the exact
+/// prompt embedded. Saving keeps only the entries used since loading, so
+/// capabilities that changed or went away don't pile up.
 pub struct EmbeddingCache {
     cache_path: PathBuf,
     cache: HashMap<String, Vec<f32>>,
+    used: HashMap<String, Vec<f32>>, // Looked up or stored this run
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,40 +37,40 @@ impl EmbeddingCache {
             HashMap::new()
         };
 
-        Ok(Self { cache_path, cache })
+        Ok(Self {
+            cache_path,
+            cache,
+            used: HashMap::new(),
+        })
     }
 
-    /// Generate hash for a capability
-    fn hash_capability(name: &str, code: &str, kind: &str) -> String {
-        let mut hasher = DefaultHasher::new();
-        name.hash(&mut hasher);
-        code.hash(&mut hasher);
-        kind.hash(&mut hasher);
-        format!("{:x}", hasher.finish())
+    /// Generate hash for an embedding request
+    fn hash_prompt(model: &str, prompt: &str) -> String {
+        content_hash(format!("{}\n{}", model, prompt).as_bytes()).unwrap_or_default()
     }
 
     /// Get cached embedding
-    pub fn get(&self, name: &str, code: &str, kind: &str) -> Option<Vec<f32>> {
-        let hash = Self::hash_capability(name, code, kind);
-        self.cache.get(&hash).cloned()
+    pub fn get(&mut self, model: &str, prompt: &str) -> Option<Vec<f32>> {
+        let hash = Self::hash_prompt(model, prompt);
+        let embedding = self.used.get(&hash).or_else(|| self.cache.get(&hash))?.clone();
+        self.used.insert(hash, embedding.clone());
+        Some(embedding)
     }
 
     /// Store embedding in cache
-    pub fn set(&mut self, name: &str, code: &str, kind: &str, embedding: Vec<f32>) -> Result<()> {
-        let hash = Self::hash_capability(name, code, kind);
-        self.cache.insert(hash, embedding);
-        self.save()?;
-        Ok(())
+    pub fn set(&mut self, model: &str, prompt: &str, embedding: Vec<f32>) {
+        let hash = Self::hash_prompt(model, prompt);
+        self.used.insert(hash, embedding);
     }
 
-    /// Save cache to disk
-    fn save(&self) -> Result<()> {
+    /// Save the entries used this run to disk
+    pub fn save(&self) -> Result<()> {
         if let Some(parent) = self.cache_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
         let entries: Vec<CacheEntry> = self
-            .cache
+            .used
             .iter()
             .map(|(hash, embedding)| CacheEntry {
                 hash: hash.clone(),
@@ -75,14 +78,31 @@ impl EmbeddingCache {
             })
             .collect();
 
-        let content = serde_json::to_string_pretty(&entries)?;
-        fs::write(&self.cache_path, content)?;
+        fs::write(&self.cache_path, serde_json::to_string(&entries)?)?;
         Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Get cache stats
-    pub fn stats(&self) -> (usize, usize) {
-        (self.cache.len(), self.cache.values().map(|v| v.len()).sum::<usize>())
+    #[test]
+    fn test_keeps_only_used_embeddings() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = EmbeddingCache::new(dir.path()).unwrap();
+        cache.set("model", "fn a", vec![1.0]);
+        cache.set("model", "fn b", vec![2.0]);
+        cache.save().unwrap();
+
+        let mut cache = EmbeddingCache::new(dir.path()).unwrap();
+        assert_eq!(cache.get("model", "fn a"), Some(vec![1.0]));
+        assert_eq!(cache.get("other-model", "fn a"), None);
+        cache.save().unwrap();
+
+        // fn b wasn't needed by the last run
+        let mut cache = EmbeddingCache::new(dir.path()).unwrap();
+        assert_eq!(cache.get("model", "fn b"), None);
+        assert_eq!(cache.get("model", "fn a"), Some(vec![1.0]));
     }
 }
-
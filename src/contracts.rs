@@ -0,0 +1,500 @@
+use anyhow::Result;
+use regex::Regex;
+use std::path::Path;
+
+use crate::extractors::{brace_block_end, capability_from_span, qualify_by_containment};
+use crate::shredder::{Capability, CapabilityKind, HttpRoute};
+use crate::spans::{describe, indented_block_end, key_offset};
+
+/// Operation keys of an OpenAPI path item
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// GraphQL root types whose fields are the API's operations
+const GRAPHQL_ROOTS: &[&str] = &["Query", "Mutation", "Subscription"];
+
+/// Column-list entries of `CREATE TABLE` that are constraints, not columns
+const SQL_CONSTRAINTS: &[&str] = &[
+    "CONSTRAINT", "PRIMARY", "FOREIGN", "UNIQUE", "CHECK", "KEY", "INDEX", "EXCLUDE", "LIKE", "PERIOD",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ContractFormat {
+    OpenApi,
+    GraphQl,
+    Protobuf,
+    Sql,
+}
+
+/// Whether a file is an API contract the analyzer understands: an OpenAPI or
+/// Swagger document, GraphQL SDL, a protobuf definition or a SQL schema
+pub fn is_contract(path: &Path) -> bool {
+    contract_format(path).is_some()
+}
+
+fn contract_format(path: &Path) -> Option<ContractFormat> {
+    let file_name = path.file_name()?.to_str()?.to_lowercase();
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "graphql" | "graphqls" | "gql" => Some(ContractFormat::GraphQl),
+        "proto" => Some(ContractFormat::Protobuf),
+        "sql" => Some(ContractFormat::Sql),
+        "yaml" | "yml" | "json" if file_name.contains("openapi") || file_name.contains("swagger") => {
+            Some(ContractFormat::OpenApi)
+        }
+        _ => None,
+    }
+}
+
+/// THE CONTRACTS: Capabilities declared by API contracts - one per OpenAPI
+/// endpoint, gRPC method, GraphQL operation or type, and database table.
+/// Each gets a generated description (`GET /users/{id} endpoint: Fetch a
+/// user`) as its doc comment, so it embeds and matches like code does. The
+/// format comes from `path`.
+pub fn analyze_source(path: &Path, source: &str) -> Result<Vec<Capability>> {
+    Ok(match contract_format(path) {
+        Some(ContractFormat::OpenApi) => openapi(source)?,
+        Some(ContractFormat::GraphQl) => graphql(source),
+        Some(ContractFormat::Protobuf) => protobuf(source),
+        Some(ContractFormat::Sql) => sql(source),
+        None => Vec::new(),
+    })
+}
+
+/// One ApiRoute per operation under `paths`
+fn openapi(source: &str) -> Result<Vec<Capability>> {
+    // YAML is a superset of JSON, so this reads both encodings
+    let document: serde_yaml::Value = serde_yaml::from_str(source)?;
+    if document.get("openapi").is_none() && document.get("swagger").is_none() {
+        return Ok(Vec::new());
+    }
+    let paths = match document.get("paths").and_then(|p| p.as_mapping()) {
+        Some(paths) => paths,
+        None => return Ok(Vec::new()),
+    };
+    let paths_offset = key_offset(source, "paths", 0).unwrap_or(0);
+
+    let mut capabilities = Vec::new();
+    for (route, item) in paths {
+        let route = match route.as_str() {
+            Some(route) => route,
+            None => continue,
+        };
+        let route_offset = key_offset(source, route, paths_offset);
+        let shared_parameters = item.get("parameters");
+
+        for method in HTTP_METHODS {
+            let operation = match item.get(*method) {
+                Some(operation) => operation,
+                None => continue,
+            };
+            let text = |key: &str| operation.get(key).and_then(|v| v.as_str()).map(str::trim);
+            let method_name = method.to_uppercase();
+
+            let (start, end) = route_offset
+                .and_then(|from| key_offset(source, method, from))
+                .map(|start| (start, indented_block_end(source, start)))
+                .unwrap_or((0, source.trim_end().len()));
+            let name = text("operationId")
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{} {}", method_name, route));
+
+            let mut description = format!("{} {} endpoint", method_name, route);
+            if let Some(summary) = text("summary") {
+                description.push_str(&format!(": {}", summary.trim_end_matches('.')));
+            }
+            description.push('.');
+            if let Some(details) = text("description").filter(|d| !d.is_empty()) {
+                description.push_str(&format!(" {}", details));
+            }
+            let parameters: Vec<&str> = [shared_parameters, operation.get("parameters")]
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p.as_sequence())
+                .flatten()
+                .filter_map(|p| p.get("name").and_then(|n| n.as_str()))
+                .collect();
+            if !parameters.is_empty() {
+                description.push_str(&format!(" Parameters: {}.", parameters.join(", ")));
+            }
+
+            let mut cap = capability_from_span(name, CapabilityKind::ApiRoute, source, start, end);
+            cap.signature = format!("{} {}", method_name, route);
+            cap.route = Some(HttpRoute {
+                method: method_name,
+                path: route.to_string(),
+            });
+            cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+            capabilities.push(cap);
+        }
+    }
+    Ok(capabilities)
+}
+
+/// Root operation fields (ApiRoute) and named types of a GraphQL schema
+fn graphql(source: &str) -> Vec<Capability> {
+    let code = blank_block_strings(source);
+    let definition_regex =
+        Regex::new(r"(?m)^[ \t]*(?:extend[ \t]+)?(type|input|interface|enum|union|scalar)[ \t]+(\w+)")
+            .expect("valid definition regex");
+    let field_regex =
+        Regex::new(r"(?m)^[ \t]*(\w+)[ \t]*(\([^)]*\))?[ \t]*:[ \t]*([\[\]\w!]+)").expect("valid field regex");
+    let value_regex = Regex::new(r"(?m)^[ \t]*([A-Za-z_]\w*)").expect("valid value regex");
+
+    let mut capabilities = Vec::new();
+    for caps in definition_regex.captures_iter(&code) {
+        let keyword = &caps[1];
+        let name = caps[2].to_string();
+        let whole = caps.get(0).expect("match");
+        let start = whole.start() + (whole.as_str().len() - whole.as_str().trim_start().len());
+        let line_end = code[start..].find('\n').map(|i| start + i).unwrap_or(code.len());
+        let body_start = code[start..line_end].find('{').map(|i| start + i + 1);
+        let end = match body_start {
+            Some(_) => brace_block_end(&code, start),
+            None => line_end,
+        };
+        let body = body_start.map(|b| &code[b..end.max(b)]).unwrap_or("");
+
+        if keyword == "type" && GRAPHQL_ROOTS.contains(&name.as_str()) {
+            let operation = name.to_lowercase();
+            for field in field_regex.captures_iter(body) {
+                let whole = field.get(0).expect("match");
+                let offset = body_start.unwrap_or(0);
+                let field_start = offset + whole.start() + (whole.as_str().len() - whole.as_str().trim_start().len());
+                let field_end = offset + whole.end();
+                let field_name = field[1].to_string();
+                let arguments = field.get(2).map(|a| a.as_str().split_whitespace().collect::<Vec<_>>().join(" "));
+
+                let mut cap = capability_from_span(field_name.clone(), CapabilityKind::ApiRoute, source, field_start, field_end);
+                cap.qualified_name = format!("{}.{}", name, field_name);
+                let description = format!(
+                    "GraphQL {} {}{}: {}",
+                    operation,
+                    field_name,
+                    arguments.unwrap_or_default(),
+                    &field[3]
+                );
+                let comment = graphql_description(source, field_start).or(cap.doc_comment.take());
+                cap.doc_comment = Some(describe(description, comment));
+                capabilities.push(cap);
+            }
+            continue;
+        }
+
+        let (kind, description) = match keyword {
+            "enum" => {
+                let values: Vec<&str> = value_regex.captures_iter(body).filter_map(|v| v.get(1)).map(|v| v.as_str()).collect();
+                (CapabilityKind::Enum, format!("GraphQL enum {} with values {}", name, values.join(", ")))
+            }
+            "union" => {
+                let members = code[start..line_end].split_once('=').map(|(_, m)| m.trim()).unwrap_or("");
+                (CapabilityKind::Schema, format!("GraphQL union {} of {}", name, members))
+            }
+            "scalar" => (CapabilityKind::Schema, format!("GraphQL scalar {}", name)),
+            _ => {
+                let fields: Vec<&str> = field_regex.captures_iter(body).filter_map(|f| f.get(1)).map(|f| f.as_str()).collect();
+                let kind = if keyword == "interface" { CapabilityKind::Interface } else { CapabilityKind::Schema };
+                (kind, format!("GraphQL {} {} with fields {}", keyword, name, fields.join(", ")))
+            }
+        };
+        let mut cap = capability_from_span(name, kind, source, start, end);
+        let comment = graphql_description(source, start).or(cap.doc_comment.take());
+        cap.doc_comment = Some(describe(description, comment));
+        capabilities.push(cap);
+    }
+    capabilities
+}
+
+/// `source` with `"""` block strings blanked out (byte offsets preserved),
+/// so prose in descriptions isn't read as fields
+fn blank_block_strings(source: &str) -> String {
+    let mut bytes = source.as_bytes().to_vec();
+    let mut inside = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"\"\"\"") {
+            inside = !inside;
+            bytes[i..i + 3].fill(b' ');
+            i += 3;
+            continue;
+        }
+        if inside && bytes[i] != b'\n' {
+            bytes[i] = b' ';
+        }
+        i += 1;
+    }
+    String::from_utf8(bytes).unwrap_or_else(|_| source.to_string())
+}
+
+/// GraphQL description string (`"""..."""` or `"..."`) directly above the
+/// line containing `start`
+fn graphql_description(source: &str, start: usize) -> Option<String> {
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let before = source[..line_start].trim_end();
+    let text = match before.strip_suffix("\"\"\"") {
+        Some(rest) => &rest[rest.rfind("\"\"\"")? + 3..],
+        None => before.lines().last()?.trim().strip_prefix('"')?.strip_suffix('"')?,
+    };
+    let text = text.lines().map(str::trim).collect::<Vec<_>>().join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Services (with one ApiRoute per RPC), messages and enums of a `.proto` file
+fn protobuf(source: &str) -> Vec<Capability> {
+    let package = Regex::new(r"(?m)^\s*package\s+([\w.]+)\s*;")
+        .expect("valid package regex")
+        .captures(source)
+        .map(|c| c[1].to_string());
+    let definition_regex =
+        Regex::new(r"(?m)^[ \t]*(service|message|enum)\s+(\w+)\s*\{").expect("valid definition regex");
+    let rpc_regex = Regex::new(
+        r"\brpc\s+(\w+)\s*\(\s*(stream\s+)?([\w.]+)\s*\)\s*returns\s*\(\s*(stream\s+)?([\w.]+)\s*\)",
+    )
+    .expect("valid rpc regex");
+    let field_regex = Regex::new(r"(?m)^\s*(?:repeated\s+|optional\s+)?(?:map<[^>]*>|[\w.]+)\s+(\w+)\s*=\s*\d+")
+        .expect("valid field regex");
+    let value_regex = Regex::new(r"(?m)^\s*(\w+)\s*=\s*-?\d+").expect("valid value regex");
+
+    let mut capabilities = Vec::new();
+    for caps in definition_regex.captures_iter(source) {
+        let keyword = &caps[1];
+        let name = caps[2].to_string();
+        let start = caps.get(1).map(|m| m.start()).unwrap_or(0);
+        let end = brace_block_end(source, start);
+        let members = top_level_lines(&source[start..end]);
+
+        match keyword {
+            "service" => {
+                let mut methods = Vec::new();
+                for rpc in rpc_regex.captures_iter(&source[start..end]) {
+                    let rpc_start = start + rpc.get(0).map(|m| m.start()).unwrap_or(0);
+                    let rpc_end = brace_block_end(source, rpc_start);
+                    let method = rpc[1].to_string();
+                    let path = match &package {
+                        Some(package) => format!("/{}.{}/{}", package, name, method),
+                        None => format!("/{}/{}", name, method),
+                    };
+                    let streaming = |i: usize| if rpc.get(i).is_some() { "a stream of " } else { "" };
+                    let description = format!(
+                        "gRPC method {}.{}: takes {}{}, returns {}{}",
+                        name,
+                        method,
+                        streaming(2),
+                        &rpc[3],
+                        streaming(4),
+                        &rpc[5]
+                    );
+
+                    let mut cap = capability_from_span(method.clone(), CapabilityKind::ApiRoute, source, rpc_start, rpc_end);
+                    cap.route = Some(HttpRoute {
+                        method: "POST".to_string(), // gRPC calls are HTTP/2 POSTs
+                        path,
+                    });
+                    cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+                    methods.push(method);
+                    capabilities.push(cap);
+                }
+                let mut cap = capability_from_span(name.clone(), CapabilityKind::Interface, source, start, end);
+                let description = format!("gRPC service {} with methods {}", name, methods.join(", "));
+                cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+                capabilities.push(cap);
+            }
+            "message" => {
+                let fields: Vec<&str> = field_regex.captures_iter(&members).filter_map(|f| f.get(1)).map(|f| f.as_str()).collect();
+                let mut cap = capability_from_span(name.clone(), CapabilityKind::Schema, source, start, end);
+                let description = format!("Protobuf message {} with fields {}", name, fields.join(", "));
+                cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+                capabilities.push(cap);
+            }
+            _ => {
+                let values: Vec<&str> = value_regex.captures_iter(&members).filter_map(|v| v.get(1)).map(|v| v.as_str()).collect();
+                let mut cap = capability_from_span(name.clone(), CapabilityKind::Enum, source, start, end);
+                let description = format!("Protobuf enum {} with values {}", name, values.join(", "));
+                cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+                capabilities.push(cap);
+            }
+        }
+    }
+    capabilities.sort_by_key(|c| c.byte_range.0);
+    qualify_by_containment(&mut capabilities, ".");
+    capabilities
+}
+
+/// Lines directly inside a `{ ... }` block, skipping nested blocks
+fn top_level_lines(block: &str) -> String {
+    let mut depth = 0i32;
+    let mut lines = String::new();
+    for line in block.lines() {
+        if depth == 1 {
+            lines.push_str(line);
+            lines.push('\n');
+        }
+        depth += line.matches('{').count() as i32 - line.matches('}').count() as i32;
+    }
+    lines
+}
+
+/// One Schema capability per `CREATE TABLE` (migrations and schema dumps)
+fn sql(source: &str) -> Vec<Capability> {
+    let table_regex = Regex::new(
+        r#"(?im)^[ \t]*create\s+(?:or\s+replace\s+)?(?:(?:global|local)\s+)?(?:(?:temporary|temp|unlogged)\s+)?table\s+(?:if\s+not\s+exists\s+)?([\w."`\[\]]+)\s*\("#,
+    )
+    .expect("valid table regex");
+    let reference_regex = Regex::new(r#"(?i)\breferences\s+([\w."`\[\]]+)"#).expect("valid references regex");
+
+    let mut capabilities = Vec::new();
+    for caps in table_regex.captures_iter(source) {
+        let whole = caps.get(0).expect("match");
+        let start = whole.start() + (whole.as_str().len() - whole.as_str().trim_start().len());
+        let open = whole.end() - 1;
+        let close = matching_paren(source, open);
+        let end = if source[close..].starts_with(';') { close + 1 } else { close };
+
+        let qualified_name = unquote(&caps[1]);
+        let name = qualified_name.rsplit('.').next().unwrap_or(&qualified_name).to_string();
+        let definitions: String = source[open + 1..close.saturating_sub(1).max(open + 1)]
+            .lines()
+            .map(|line| line.split("--").next().unwrap_or(""))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let columns: Vec<String> = split_top_level(&definitions)
+            .into_iter()
+            .filter_map(|definition| definition.split_whitespace().next().map(unquote))
+            .filter(|column| !SQL_CONSTRAINTS.contains(&column.to_uppercase().as_str()))
+            .collect();
+        let mut references: Vec<String> = reference_regex.captures_iter(&definitions).map(|r| unquote(&r[1])).collect();
+        references.dedup();
+
+        let mut description = format!("Database table {} with columns {}", name, columns.join(", "));
+        if !references.is_empty() {
+            description.push_str(&format!("; references {}", references.join(", ")));
+        }
+        let mut cap = capability_from_span(name, CapabilityKind::Schema, source, start, end);
+        cap.qualified_name = qualified_name;
+        cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+        capabilities.push(cap);
+    }
+    capabilities
+}
+
+/// Byte offset just past the `)` closing the `(` at `open` (quotes respected)
+fn matching_paren(source: &str, open: usize) -> usize {
+    let mut depth = 0usize;
+    let mut quoted = false;
+    for (i, c) in source[open..].char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    source.len()
+}
+
+/// Comma-separated entries outside parentheses: `id INT, total NUMERIC(10, 2)`
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0i32;
+    let mut from = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                entries.push(list[from..i].trim());
+                from = i + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(list[from..].trim());
+    entries.into_iter().filter(|e| !e.is_empty()).collect()
+}
+
+/// Identifier without SQL quoting: `"public"."orders"` -> `public.orders`
+fn unquote(identifier: &str) -> String {
+    identifier.chars().filter(|c| !matches!(c, '"' | '`' | '[' | ']')).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_operations() {
+        let source = "openapi: 3.0.0\ninfo:\n  title: Shop\npaths:\n  /users/{id}:\n    parameters:\n    - name: id\n      in: path\n    get:\n      operationId: getUser\n      summary: Fetch a user\n      responses:\n        '200':\n          description: OK\n    delete:\n      summary: Remove a user\n  /orders:\n    post:\n      summary: Place an order.\n";
+        let caps = analyze_source(Path::new("api/openapi.yaml"), source).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.line, c.end_line)).collect();
+        assert_eq!(
+            summary,
+            vec![("getUser", 9, 14), ("DELETE /users/{id}", 15, 16), ("POST /orders", 18, 19)]
+        );
+        assert_eq!(caps[0].kind, CapabilityKind::ApiRoute);
+        assert_eq!(caps[0].route.as_ref().map(|r| r.path.as_str()), Some("/users/{id}"));
+        assert_eq!(
+            caps[0].doc_comment.as_deref(),
+            Some("GET /users/{id} endpoint: Fetch a user. Parameters: id.")
+        );
+        assert_eq!(caps[2].doc_comment.as_deref(), Some("POST /orders endpoint: Place an order."));
+
+        // Other YAML isn't a contract
+        assert!(!is_contract(Path::new("docker-compose.yml")));
+    }
+
+    #[test]
+    fn test_schema_contracts() {
+        let graphql = "\"\"\"\nA shopper: has an account\n\"\"\"\ntype User {\n  id: ID!\n  email: String\n}\n\ntype Query {\n  \"Look up a user\"\n  user(id: ID!): User\n  users: [User!]!\n}\n\nenum Role {\n  ADMIN\n  MEMBER\n}\n";
+        let caps = analyze_source(Path::new("schema.graphql"), graphql).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.qualified_name.as_str(), c.kind, c.line)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("User", CapabilityKind::Schema, 4),
+                ("Query.user", CapabilityKind::ApiRoute, 11),
+                ("Query.users", CapabilityKind::ApiRoute, 12),
+                ("Role", CapabilityKind::Enum, 15),
+            ]
+        );
+        assert_eq!(
+            caps[0].doc_comment.as_deref(),
+            Some("GraphQL type User with fields id, email\nA shopper: has an account")
+        );
+        assert_eq!(caps[1].doc_comment.as_deref(), Some("GraphQL query user(id: ID!): User\nLook up a user"));
+
+        let proto = "syntax = \"proto3\";\npackage shop.v1;\n\n// Charges cards\nservice Billing {\n  rpc Charge(ChargeRequest) returns (ChargeResponse);\n  rpc Watch(WatchRequest) returns (stream Event) {}\n}\n\nmessage ChargeRequest {\n  int64 amount = 1;\n  repeated string tags = 2;\n  message Card {\n    string number = 1;\n  }\n}\n";
+        let caps = analyze_source(Path::new("billing.proto"), proto).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.qualified_name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Billing", CapabilityKind::Interface),
+                ("Billing.Charge", CapabilityKind::ApiRoute),
+                ("Billing.Watch", CapabilityKind::ApiRoute),
+                ("ChargeRequest", CapabilityKind::Schema),
+                ("ChargeRequest.Card", CapabilityKind::Schema),
+            ]
+        );
+        assert_eq!(caps[0].doc_comment.as_deref(), Some("gRPC service Billing with methods Charge, Watch\nCharges cards"));
+        assert_eq!(caps[1].route.as_ref().map(|r| r.path.as_str()), Some("/shop.v1.Billing/Charge"));
+        assert_eq!(
+            caps[2].doc_comment.as_deref(),
+            Some("gRPC method Billing.Watch: takes WatchRequest, returns a stream of Event")
+        );
+        assert_eq!(caps[3].doc_comment.as_deref(), Some("Protobuf message ChargeRequest with fields amount, tags"));
+
+        let migration = "-- orders placed by users\nCREATE TABLE IF NOT EXISTS \"public\".\"orders\" (\n  id BIGSERIAL PRIMARY KEY,\n  user_id BIGINT NOT NULL REFERENCES users(id),\n  total NUMERIC(10, 2), -- in dollars\n  CONSTRAINT positive CHECK (total > 0)\n);\n";
+        let caps = analyze_source(Path::new("migrations/001_orders.sql"), migration).unwrap();
+        assert_eq!(caps.len(), 1);
+        assert_eq!((caps[0].name.as_str(), caps[0].qualified_name.as_str()), ("orders", "public.orders"));
+        assert_eq!((caps[0].line, caps[0].end_line), (2, 7));
+        assert_eq!(
+            caps[0].doc_comment.as_deref(),
+            Some("Database table orders with columns id, user_id, total; references users")
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use anyhow::Result;
+use git2::{Delta, DiffFindOptions, DiffOptions, Oid, Repository, RevparseMode, Tree};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::analyzer::Analyzer;
+use crate::history::{read_tree_file, sibling};
+use crate::shredder::{Capability, CapabilityKind, Shredder};
+
+/// Capabilities added, modified and removed between two revisions
+#[derive(Debug, Serialize)]
+pub struct CapabilityDelta {
+    pub since: String,
+    pub until: String, // A revision, or "WORKTREE" for uncommitted changes
+    pub files_changed: usize,
+    pub added: Vec<DeltaEntry>,
+    pub modified: Vec<DeltaEntry>,
+    pub removed: Vec<DeltaEntry>,
+}
+
+/// One capability in a delta. Paths are relative to the repository root;
+/// `path`/`line` are the old location for removed capabilities.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeltaEntry {
+    pub path: String,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: CapabilityKind,
+    pub line: usize,
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_path: Option<String>, // Set when the file was renamed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_line: Option<usize>,
+}
+
+/// THE DELTA: Capability changes between two revisions, found by shredding
+/// only the files git reports as changed.
+///
+/// `since` is any revision (`main`, `HEAD~3`, a SHA) or a range (`v1.0..v1.1`).
+/// Without a range end or `until`, the working tree (including untracked
+/// files) is compared. Capabilities are matched by kind and qualified name; a
+/// match whose code differs beyond whitespace is modified.
+pub fn capability_delta(
+    repo: &Repository,
+    since: &str,
+    until: Option<&str>,
+    shredder: &mut Shredder,
+) -> Result<CapabilityDelta> {
+    let spec = repo.revparse(since)?;
+    let from = spec
+        .from()
+        .ok_or_else(|| anyhow::anyhow!("Revision '{}' has no start", since))?;
+    let old_tree = from.peel_to_tree()?;
+    let until_rev = match (spec.mode().contains(RevparseMode::RANGE), until) {
+        (true, _) => since.split("..").last().map(|to| to.trim_start_matches('.').to_string()),
+        (false, until) => until.map(str::to_string),
+    };
+    let new_tree: Option<Tree> = match (spec.mode().contains(RevparseMode::RANGE), until) {
+        (true, _) => Some(
+            spec.to()
+                .ok_or_else(|| anyhow::anyhow!("Revision range '{}' has no end", since))?
+                .peel_to_tree()?,
+        ),
+        (false, Some(until)) => Some(repo.revparse_single(until)?.peel_to_tree()?),
+        (false, None) => None,
+    };
+
+    let mut options = DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let mut diff = match &new_tree {
+        Some(tree) => repo.diff_tree_to_tree(Some(&old_tree), Some(tree), Some(&mut options))?,
+        None => repo.diff_tree_to_workdir_with_index(Some(&old_tree), Some(&mut options))?,
+    };
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    let workdir = repo.workdir().map(Path::to_path_buf);
+    let blob_text = |id: Oid| -> Option<String> {
+        let blob = repo.find_blob(id).ok()?;
+        String::from_utf8(blob.content().to_vec()).ok()
+    };
+
+    let mut delta = CapabilityDelta {
+        since: from.id().to_string(),
+        until: until_rev.unwrap_or_else(|| "WORKTREE".to_string()),
+        files_changed: 0,
+        added: Vec::new(),
+        modified: Vec::new(),
+        removed: Vec::new(),
+    };
+    for file in diff.deltas() {
+        let old_path = file.old_file().path().map(Path::to_path_buf);
+        let new_path = file.new_file().path().map(Path::to_path_buf);
+        let old_source = match file.status() {
+            Delta::Added | Delta::Untracked => None,
+            _ => blob_text(file.old_file().id()),
+        };
+        let new_source = match file.status() {
+            Delta::Deleted => None,
+            _ if new_tree.is_some() => blob_text(file.new_file().id()),
+            _ => new_path
+                .as_ref()
+                .zip(workdir.as_ref())
+                .and_then(|(path, workdir)| fs::read_to_string(workdir.join(path)).ok()),
+        };
+
+        // Manifests read their lockfiles from the same side of the diff
+        let old_caps = match (&old_path, &old_source) {
+            (Some(path), Some(source)) => {
+                let read_lockfile = |lockfile: &str| read_tree_file(repo, &old_tree, &sibling(path, lockfile));
+                capabilities(shredder, path, source, &read_lockfile)
+            }
+            _ => Vec::new(),
+        };
+        let new_caps = match (&new_path, &new_source) {
+            (Some(path), Some(source)) => {
+                let read_lockfile = |lockfile: &str| match (&new_tree, &workdir) {
+                    (Some(tree), _) => read_tree_file(repo, tree, &sibling(path, lockfile)),
+                    (None, Some(workdir)) => fs::read_to_string(workdir.join(sibling(path, lockfile))).ok(),
+                    (None, None) => None,
+                };
+                capabilities(shredder, path, source, &read_lockfile)
+            }
+            _ => Vec::new(),
+        };
+        if old_caps.is_empty() && new_caps.is_empty() {
+            continue;
+        }
+        delta.files_changed += 1;
+
+        let old_path = old_path.map(|p| p.display().to_string()).unwrap_or_default();
+        let new_path = new_path.map(|p| p.display().to_string()).unwrap_or_else(|| old_path.clone());
+        let renamed_from = (file.status() == Delta::Renamed).then(|| old_path.clone());
+
+        let old_keyed = keyed(&old_caps);
+        let new_keyed = keyed(&new_caps);
+        for (key, new_cap) in &new_keyed {
+            let mut entry = entry(&new_path, new_cap);
+            entry.previous_path = renamed_from.clone();
+            match old_keyed.get(key) {
+                None => delta.added.push(entry),
+                Some(old_cap) => {
+                    let old_body = body(old_cap, old_source.as_deref().unwrap_or(""));
+                    let new_body = body(new_cap, new_source.as_deref().unwrap_or(""));
+                    if old_body != new_body {
+                        entry.previous_line = Some(old_cap.line);
+                        delta.modified.push(entry);
+                    }
+                }
+            }
+        }
+        for (key, old_cap) in &old_keyed {
+            if !new_keyed.contains_key(key) {
+                delta.removed.push(self::entry(&old_path, old_cap));
+            }
+        }
+    }
+
+    for entries in [&mut delta.added, &mut delta.modified, &mut delta.removed] {
+        entries.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    }
+    Ok(delta)
+}
+
+/// Capabilities of a file's content at one revision, tests aside;
+/// `read_lockfile` reads a manifest's lockfiles at the same revision
+fn capabilities(
+    shredder: &mut Shredder,
+    path: &Path,
+    source: &str,
+    read_lockfile: &dyn Fn(&str) -> Option<String>,
+) -> Vec<Capability> {
+    Analyzer::for_path(path)
+        .analyze(shredder, path, source, read_lockfile)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| c.kind != CapabilityKind::Test)
+        .collect()
+}
+
+/// Capabilities keyed by kind, qualified name and occurrence (for overloads)
+fn keyed(capabilities: &[Capability]) -> HashMap<(CapabilityKind, String, usize), &Capability> {
+    let mut seen: HashMap<(CapabilityKind, &str), usize> = HashMap::new();
+    capabilities
+        .iter()
+        .map(|cap| {
+            let occurrence = seen.entry((cap.kind, cap.qualified_name.as_str())).or_default();
+            *occurrence += 1;
+            ((cap.kind, cap.qualified_name.clone(), *occurrence), cap)
+        })
+        .collect()
+}
+
+/// A capability's code with whitespace collapsed, so reformatting isn't a change
+fn body(cap: &Capability, source: &str) -> String {
+    let code = match cap.cell {
+        Some(_) => cap.code_snippet.as_str(), // Byte ranges are within the notebook cell
+        None => source.get(cap.byte_range.0..cap.byte_range.1).unwrap_or(&cap.code_snippet),
+    };
+    code.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn entry(path: &str, cap: &Capability) -> DeltaEntry {
+    DeltaEntry {
+        path: path.to_string(),
+        name: cap.name.clone(),
+        qualified_name: cap.qualified_name.clone(),
+        kind: cap.kind,
+        line: cap.line,
+        signature: cap.signature.clone(),
+        previous_path: None,
+        previous_line: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+
+    fn commit(repo: &Repository, files: &[(&str, &str)], message: &str) -> Oid {
+        let workdir = repo.workdir().unwrap().to_path_buf();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            fs::write(workdir.join(path), content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Dev", "dev@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reports_added_modified_and_removed_capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let first = commit(
+            &repo,
+            &[
+                ("billing.py", "def charge(amount):\n    return amount\n\ndef refund(amount):\n    return -amount\n"),
+                ("notes.txt", "todo\n"),
+            ],
+            "first",
+        );
+        commit(
+            &repo,
+            &[
+                // Reformatted `refund` is unchanged; `charge` gains a fee
+                ("billing.py", "def charge(amount):\n    return amount + 1\n\ndef refund(amount):\n    return  -amount\n\ndef invoice(order):\n    pass\n"),
+                ("notes.txt", "done\n"),
+                ("Cargo.toml", "[dependencies]\ntokio = \"1\"\n"),
+            ],
+            "second",
+        );
+        fs::write(dir.path().join("cli.py"), "def main():\n    pass\n").unwrap();
+
+        let mut shredder = Shredder::new().unwrap();
+        let range = format!("{}..HEAD", first);
+        let delta = capability_delta(&repo, &range, None, &mut shredder).unwrap();
+        let names = |entries: &[DeltaEntry]| entries.iter().map(|e| e.qualified_name.clone()).collect::<Vec<_>>();
+        assert_eq!(delta.until, "HEAD");
+        assert_eq!(delta.files_changed, 2);
+        assert_eq!(names(&delta.added), vec!["tokio", "invoice"]);
+        assert_eq!(names(&delta.modified), vec!["charge"]);
+        assert!(delta.removed.is_empty());
+
+        // Against the working tree, the untracked file counts too
+        let delta = capability_delta(&repo, &first.to_string(), None, &mut shredder).unwrap();
+        assert_eq!(delta.until, "WORKTREE");
+        assert_eq!(names(&delta.added), vec!["tokio", "invoice", "main"]);
+        assert_eq!(delta.added[2].path, "cli.py");
+
+        // And backwards, the new capabilities are removed
+        let delta = capability_delta(&repo, "HEAD", Some(&first.to_string()), &mut shredder).unwrap();
+        assert_eq!(names(&delta.removed), vec!["tokio", "invoice"]);
+        assert_eq!(delta.removed[1].line, 7);
+    }
+}
@@ -111,12 +111,16 @@ impl Deployer {
             Bounty: {}\n\
             Description: {}\n\
             Capability: {}\n\
+            Signature: {}\n\
+            Documentation: {}\n\
             Code:\n{}\n\n\
             Generate a simple main file or entry point that uses this capability to fulfill the bounty. \
             Keep it concise and working. Output only the code, no explanations.",
             m.need.title,
             m.need.description,
-            m.capability.name,
+            m.capability.qualified_name.as_ref().unwrap_or(&m.capability.name),
+            m.capability.signature.as_deref().unwrap_or("unknown"),
+            m.capability.doc_comment.as_deref().unwrap_or("none"),
             m.capability.code_snippet.chars().take(500).collect::<String>()
         );
 
@@ -0,0 +1,190 @@
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::shredder::{Capability, CapabilityKind, TestEvidence};
+
+/// THE EVIDENCE: Attaches test capabilities to the capabilities they exercise.
+///
+/// A test exercises a capability when its body references the capability's
+/// name, or when its own name is the capability's name with a `test` affix
+/// (`test_parse_config`, `TestParseConfig` -> `parse_config`). Matches in the
+/// test's own file or its subject file (`parser.test.ts` -> `parser.ts`) win;
+/// otherwise only capabilities with a unique name are credited. Tests are
+/// removed from the returned list.
+pub fn attach_tests(capabilities: Vec<(PathBuf, String, Capability)>) -> Vec<(PathBuf, String, Capability)> {
+    let (tests, mut subjects): (Vec<_>, Vec<_>) = capabilities
+        .into_iter()
+        .partition(|(_, _, cap)| cap.kind == CapabilityKind::Test);
+    if tests.is_empty() {
+        return subjects;
+    }
+
+    // Subjects by name and by normalized name, so each test only looks up
+    // its own identifiers
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut by_normalized: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, (_, _, cap)) in subjects.iter().enumerate().filter(|(_, (_, _, cap))| cap.name.len() > 2) {
+        by_name.entry(cap.name.as_str()).or_default().push(i);
+        by_normalized.entry(normalize(&cap.name)).or_default().push(i);
+    }
+
+    let mut links: Vec<(usize, TestEvidence)> = Vec::new();
+    for (test_path, _, test) in &tests {
+        // The full body's identifiers; the snippet is truncated for display
+        let identifiers: HashSet<&str> = match test.references.is_empty() {
+            false => test.references.iter().map(String::as_str).collect(),
+            true => identifier_regex().find_iter(&test.code_snippet).map(|m| m.as_str()).collect(),
+        };
+        let subject_name = normalize(strip_test_affixes(&test.name));
+
+        let matches: BTreeSet<usize> = identifiers
+            .iter()
+            .filter_map(|identifier| by_name.get(identifier))
+            .chain(by_normalized.get(&subject_name))
+            .flatten()
+            .copied()
+            .collect();
+        let candidates: Vec<usize> = matches
+            .into_iter()
+            .filter(|&i| {
+                // Skip the test's own enclosing class/module
+                let (path, _, cap) = &subjects[i];
+                !(path == test_path
+                    && cap.byte_range.0 <= test.byte_range.0
+                    && test.byte_range.1 <= cap.byte_range.1)
+            })
+            .collect();
+
+        let test_stem = subject_stem(test_path);
+        let related: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&i| {
+                let path = &subjects[i].0;
+                path == test_path || file_stem(path) == test_stem
+            })
+            .collect();
+        let credited = if related.is_empty() {
+            candidates
+                .into_iter()
+                .filter(|&i| by_name[subjects[i].2.name.as_str()].len() == 1)
+                .collect()
+        } else {
+            related
+        };
+
+        for i in credited {
+            links.push((
+                i,
+                TestEvidence {
+                    name: test.name.clone(),
+                    path: test_path.display().to_string(),
+                    line: test.line,
+                },
+            ));
+        }
+    }
+
+    for (i, evidence) in links {
+        let cap = &mut subjects[i].2;
+        if !cap.tests.contains(&evidence) {
+            cap.tests.push(evidence);
+            cap.metrics.has_tests = true;
+        }
+    }
+    subjects
+}
+
+/// Distinct identifiers in a test's body, for `Capability::references`
+pub fn referenced_names(body: &str) -> Vec<String> {
+    let names: BTreeSet<&str> = identifier_regex().find_iter(body).map(|m| m.as_str()).collect();
+    names.into_iter().map(str::to_string).collect()
+}
+
+fn identifier_regex() -> &'static Regex {
+    static IDENTIFIER_REGEX: OnceLock<Regex> = OnceLock::new();
+    IDENTIFIER_REGEX.get_or_init(|| Regex::new(r"[A-Za-z_]\w*").expect("valid identifier regex"))
+}
+
+/// Lowercase alphanumerics only: `parse_config`, `parseConfig` -> `parseconfig`
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// `test_parse_config` / `TestParseConfig` / `parse_config_test` -> the subject name
+fn strip_test_affixes(name: &str) -> &str {
+    let name = name
+        .strip_prefix("test_")
+        .or_else(|| name.strip_prefix("Test"))
+        .or_else(|| name.strip_prefix("test"))
+        .unwrap_or(name);
+    name.strip_suffix("_test").or_else(|| name.strip_suffix("Test")).unwrap_or(name)
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Stem of the file a test file covers: `parser.test.ts`, `parser_test.go`,
+/// `test_parser.py` and `ParserTest.java` all cover `parser`
+fn subject_stem(path: &Path) -> String {
+    let stem = file_stem(path);
+    let stem = stem.strip_prefix("test_").unwrap_or(&stem);
+    [".test", ".spec", "_test", "_spec", "tests", "test", "spec"]
+        .iter()
+        .find_map(|suffix| stem.strip_suffix(suffix))
+        .unwrap_or(stem)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shredder::Shredder;
+
+    #[test]
+    fn test_attaches_tests_to_subjects() {
+        let mut shredder = Shredder::new().unwrap();
+        let mut caps = Vec::new();
+        // `render` is only referenced past the truncated display snippet
+        let parser_tests = format!(
+            "it('reads keys', () => {{\n  expect(parseConfig('a=1')).toBeTruthy();\n}});\n\nit('draws', () => {{\n{}  render();\n}});\n",
+            "  expect(1 + 1).toBe(2);\n".repeat(40)
+        );
+        for (path, source) in [
+            ("src/parser.ts", "export function parseConfig(s: string) {}\nexport function render() {}\n"),
+            ("src/parser.test.ts", parser_tests.as_str()),
+            ("src/util.py", "def slugify(s):\n    pass\n"),
+            ("tests/test_util.py", "def test_slugify():\n    assert True\n"),
+        ] {
+            let path = PathBuf::from(path);
+            for cap in shredder.shred_source(&path, source).unwrap() {
+                caps.push((path.clone(), String::new(), cap));
+            }
+        }
+
+        let caps = attach_tests(caps);
+        let evidence: Vec<_> = caps
+            .iter()
+            .map(|(_, _, c)| (c.name.as_str(), c.tests.iter().map(|t| t.name.as_str()).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(
+            evidence,
+            vec![
+                ("parseConfig", vec!["reads keys"]),
+                ("render", vec!["draws"]),
+                ("slugify", vec!["test_slugify"]),
+            ]
+        );
+        assert_eq!(caps[0].2.tests[0].path, "src/parser.test.ts");
+        assert_eq!(caps[0].2.tests[0].line, 1);
+    }
+}
@@ -0,0 +1,221 @@
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::Language;
+
+use super::routes::{join_paths, method_from_name, unquote, with_route};
+use super::{
+    attribute_lines_before, brace_block_end, capability_from_span, qualify_by_containment,
+    LanguageExtractor, SourceFile,
+};
+use crate::shredder::{Capability, CapabilityKind};
+
+/// xUnit/NUnit/MSTest attributes that mark a test method
+const TEST_ATTRIBUTES: &[&str] = &["Fact", "Theory", "Test", "TestCase", "TestMethod"];
+
+/// ASP.NET attributes that turn an action method into an HTTP endpoint
+const ASPNET_ROUTE_ATTRIBUTES: &[&str] = &[
+    "HttpGet", "HttpPost", "HttpPut", "HttpDelete", "HttpPatch", "HttpHead", "HttpOptions",
+    "AcceptVerbs",
+];
+
+/// C# extraction (public classes, interfaces, records, structs, enums, public
+/// methods, test methods and ASP.NET `[HttpGet]`-style endpoints), from the
+/// source text.
+pub struct CSharpExtractor;
+
+impl LanguageExtractor for CSharpExtractor {
+    fn name(&self) -> &'static str {
+        "csharp"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["cs"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        None
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let source = file.source;
+        let mut capabilities = Vec::new();
+        static ATTRIBUTE_REGEX: OnceLock<Regex> = OnceLock::new();
+        let attribute_regex = ATTRIBUTE_REGEX.get_or_init(|| {
+            Regex::new(r#"(\w+)\s*(?:\(\s*(?:Template\s*=\s*)?("[^"]*")?[^)]*\))?"#).expect("valid attribute regex")
+        });
+        // (start, end, class name, [Route] template) of every public type
+        let mut type_spans: Vec<(usize, usize, String, String)> = Vec::new();
+
+        static TYPE_REGEX: OnceLock<Regex> = OnceLock::new();
+        let type_regex = TYPE_REGEX.get_or_init(|| {
+            Regex::new(
+                r"(?m)^[ \t]*public[ \t]+(?:(?:static|sealed|abstract|partial|readonly|ref|unsafe)[ \t]+)*(class|interface|record|struct|enum)(?:[ \t]+(?:class|struct))?[ \t]+([A-Za-z_]\w*)",
+            )
+            .expect("valid type regex")
+        });
+        for caps in type_regex.captures_iter(source) {
+            let decl = caps.get(0).unwrap();
+            let start = trimmed_start(decl.as_str(), decl.start());
+            let end = brace_block_end(source, decl.end());
+            let template = parse_attributes(attribute_regex, source, decl.start())
+                .into_iter()
+                .find(|(name, _)| name == "Route")
+                .and_then(|(_, template)| template)
+                .unwrap_or_default();
+            let kind = match &caps[1] {
+                "interface" => CapabilityKind::Interface,
+                "enum" => CapabilityKind::Enum,
+                _ => CapabilityKind::Class,
+            };
+            type_spans.push((start, end, caps[2].to_string(), template));
+            capabilities.push(capability_from_span(caps[2].to_string(), kind, source, start, end));
+        }
+
+        static METHOD_REGEX: OnceLock<Regex> = OnceLock::new();
+        let method_regex = METHOD_REGEX.get_or_init(|| {
+            Regex::new(
+                r"(?m)^[ \t]*public[ \t]+(?:(?:static|async|virtual|override|abstract|sealed|new|extern|unsafe|partial)[ \t]+)*([\w.\[\]?]+(?:<[^()\n]*>)?[\[\]?]*)[ \t]+([A-Za-z_]\w*)[ \t]*(?:<[^>\n]*>)?[ \t]*\(",
+            )
+            .expect("valid method regex")
+        });
+        for caps in method_regex.captures_iter(source) {
+            // `public record Point(int X, int Y)` is a type, not a method
+            if matches!(&caps[1], "class" | "interface" | "record" | "struct" | "enum") {
+                continue;
+            }
+            let decl = caps.get(0).unwrap();
+            let attributes = parse_attributes(attribute_regex, source, decl.start());
+            let capability = capability_from_span(
+                caps[2].to_string(),
+                CapabilityKind::Function,
+                source,
+                trimmed_start(decl.as_str(), decl.start()),
+                brace_block_end(source, decl.end()),
+            );
+
+            let verb = attributes
+                .iter()
+                .find(|(name, _)| ASPNET_ROUTE_ATTRIBUTES.contains(&name.as_str()));
+            capabilities.push(match verb {
+                Some((verb, template)) => {
+                    // Innermost enclosing controller provides the [Route] prefix
+                    let (controller, prefix) = type_spans
+                        .iter()
+                        .filter(|(start, end, _, _)| *start < decl.start() && decl.start() < *end)
+                        .max_by_key(|(start, _, _, _)| *start)
+                        .map(|(_, _, name, prefix)| (name.as_str(), prefix.as_str()))
+                        .unwrap_or(("", ""));
+                    let action_template = template.clone().or_else(|| {
+                        attributes
+                            .iter()
+                            .find(|(name, _)| name == "Route")
+                            .and_then(|(_, t)| t.clone())
+                    });
+                    let method = method_from_name(verb).unwrap_or("ANY");
+                    let path = aspnet_path(prefix, action_template.as_deref().unwrap_or(""), controller, &caps[2]);
+                    with_route(
+                        Capability {
+                            kind: CapabilityKind::ApiRoute,
+                            ..capability
+                        },
+                        method,
+                        &path,
+                    )
+                }
+                None if attributes.iter().any(|(name, _)| TEST_ATTRIBUTES.contains(&name.as_str())) => Capability {
+                    kind: CapabilityKind::Test,
+                    ..capability
+                },
+                None => capability,
+            });
+        }
+
+        capabilities.sort_by_key(|c| c.line);
+        qualify_by_containment(&mut capabilities, ".");
+        Ok(capabilities)
+    }
+}
+
+/// Offset of the first non-blank character of a regex match
+fn trimmed_start(matched: &str, start: usize) -> usize {
+    start + (matched.len() - matched.trim_start().len())
+}
+
+/// Attributes on the lines above a declaration as (name, template) pairs:
+/// `[HttpGet("{id}"), Authorize]` -> `[("HttpGet", Some("{id}")), ("Authorize", None)]`
+fn parse_attributes(regex: &Regex, source: &str, decl: usize) -> Vec<(String, Option<String>)> {
+    attribute_lines_before(source, decl, '[')
+        .iter()
+        .flat_map(|line| {
+            let inner = line.trim_start_matches('[').trim_end_matches(']');
+            regex
+                .captures_iter(inner)
+                .map(|caps| {
+                    let name = caps[1].trim_end_matches("Attribute").to_string();
+                    (name, caps.get(2).map(|t| unquote(t.as_str())))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Combine controller and action route templates, expanding `[controller]`
+/// and `[action]` tokens. Action templates starting with `/` or `~/` are absolute.
+fn aspnet_path(prefix: &str, template: &str, controller: &str, action: &str) -> String {
+    let controller = controller.trim_end_matches("Controller").to_lowercase();
+    let expand = |t: &str| t.replace("[controller]", &controller).replace("[action]", action);
+    if let Some(absolute) = template.strip_prefix('~').or_else(|| template.strip_prefix('/').map(|_| template)) {
+        join_paths("", &expand(absolute))
+    } else {
+        join_paths(&expand(prefix), &expand(template))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_extracts_aspnet_actions() {
+        let source = r#"
+[ApiController]
+[Route("api/[controller]")]
+public class ProductsController : ControllerBase
+{
+    [HttpGet("{id}")]
+    [ProducesResponseType(200)]
+    public async Task<ActionResult<Product>> Get(int id)
+    {
+        return await _db.Products.FindAsync(id);
+    }
+
+    public int Count() => _db.Products.Count();
+
+    private void Audit() { }
+}
+
+public record Product(int Id, string Name);
+"#;
+        let file = SourceFile {
+            path: Path::new("ProductsController.cs"),
+            source,
+            root: None,
+        };
+        let caps = CSharpExtractor.extract(&file).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ProductsController", CapabilityKind::Class),
+                ("Get", CapabilityKind::ApiRoute),
+                ("Count", CapabilityKind::Function),
+                ("Product", CapabilityKind::Class),
+            ]
+        );
+        assert_eq!(caps[2].code_snippet, "public int Count() => _db.Products.Count();");
+        let route = caps[1].route.as_ref().unwrap();
+        assert_eq!((route.method.as_str(), route.path.as_str()), ("GET", "/api/products/{id}"));
+    }
+}
@@ -0,0 +1,140 @@
+use anyhow::Result;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Node, Query};
+
+use super::routes::{method_from_name, unquote, with_route};
+use super::{capability_from_node, query, starts_uppercase, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind};
+
+static QUERY: OnceLock<Query> = OnceLock::new();
+
+/// Go extraction (exported functions/methods, types and interfaces, routes,
+/// cobra/urfave-cli commands and tests)
+pub struct GoExtractor;
+
+impl LanguageExtractor for GoExtractor {
+    fn name(&self) -> &'static str {
+        "go"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["go"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        Some(tree_sitter_go::language())
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let mut capabilities = Vec::new();
+        if let Some(root) = file.root {
+            let is_test_file = file
+                .path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.ends_with("_test.go"))
+                .unwrap_or(false);
+            extract_matches(root, file.source, &mut capabilities, is_test_file)?;
+        }
+        Ok(capabilities)
+    }
+}
+
+fn extract_matches(root: Node, source: &str, capabilities: &mut Vec<Capability>, is_test_file: bool) -> Result<()> {
+    let query = query::builtin(&QUERY, tree_sitter_go::language(), include_str!("queries/go.scm"));
+
+    for m in query::matches(query, root, source) {
+        if let Some(call) = m.node("route.call") {
+            if let Some(route) = router_route(&call, source)? {
+                capabilities.push(route);
+            }
+            continue;
+        }
+        let (kind, node) = match m.definition() {
+            Some(definition) => definition,
+            None => continue,
+        };
+        if kind == CapabilityKind::CliCommand {
+            if let Some(name) = command_name(&node, source)? {
+                capabilities.push(capability_from_node(name, kind, &node, source));
+            }
+            continue;
+        }
+        let name = match m.name(source) {
+            Some(name) => name,
+            None => continue,
+        };
+        let kind = match kind {
+            // `go test` runs `TestXxx(t *testing.T)` in _test.go files
+            CapabilityKind::Function if is_test_file && name.starts_with("Test") => CapabilityKind::Test,
+            CapabilityKind::Class if m.node("type").map(|t| t.kind()) == Some("interface_type") => {
+                CapabilityKind::Interface
+            }
+            kind => kind,
+        };
+        capabilities.push(capability_from_node(name.to_string(), kind, &node, source));
+    }
+
+    Ok(())
+}
+
+/// Command name from the `Use:` (cobra) or `Name:` (urfave/cli) field of a
+/// command literal: `Use: "serve [flags]"` -> `serve`
+fn command_name(node: &Node, source: &str) -> Result<Option<String>> {
+    let body = match node.child_by_field_name("body") {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    for i in 0..body.named_child_count() {
+        let element = body.named_child(i).unwrap();
+        if element.kind() != "keyed_element" || element.named_child_count() < 2 {
+            continue;
+        }
+        let key = element.named_child(0).unwrap().utf8_text(source.as_bytes())?;
+        let value = element.named_child(1).unwrap();
+        if matches!(key, "Use" | "Name") && value.kind() == "interpreted_string_literal" {
+            let value = unquote(value.utf8_text(source.as_bytes())?);
+            return Ok(value.split_whitespace().next().map(|n| n.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Gin/Echo (`r.GET("/users/:id", h)`), chi (`r.Get("/users/{id}", h)`) and
+/// net/http or gorilla/mux (`mux.HandleFunc("/users", h)`) registrations
+fn router_route(node: &Node, source: &str) -> Result<Option<Capability>> {
+    let field = match node.child_by_field_name("function") {
+        Some(f) if f.kind() == "selector_expression" => match f.child_by_field_name("field") {
+            Some(field) => field.utf8_text(source.as_bytes())?,
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let method = match field {
+        "Handle" | "HandleFunc" => "ANY",
+        // Method names are capitalised in Go routers; lowercase `get` is a map or cache
+        f if starts_uppercase(f) => match method_from_name(f) {
+            Some(m) => m,
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    let args = match node.child_by_field_name("arguments") {
+        Some(a) if a.named_child_count() >= 2 => a,
+        _ => return Ok(None),
+    };
+    let path = match args.named_child(0) {
+        Some(p) if p.kind() == "interpreted_string_literal" || p.kind() == "raw_string_literal" => {
+            unquote(p.utf8_text(source.as_bytes())?)
+        }
+        _ => return Ok(None),
+    };
+    if !path.starts_with('/') {
+        return Ok(None);
+    }
+
+    let name = format!("{} {}", method, path);
+    let cap = capability_from_node(name, CapabilityKind::ApiRoute, node, source);
+    Ok(Some(with_route(cap, method, &path)))
+}
@@ -0,0 +1,279 @@
+use anyhow::Result;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Node, Query};
+
+use super::routes::{join_paths, method_from_name, unquote, with_route};
+use super::{capability_from_node, query, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind, Visibility};
+
+static QUERY: OnceLock<Query> = OnceLock::new();
+
+/// Spring MVC and JAX-RS annotations that turn a method into an HTTP endpoint
+pub const JVM_ROUTE_ANNOTATIONS: &[&str] = &[
+    "GetMapping", "PostMapping", "PutMapping", "DeleteMapping", "PatchMapping", "RequestMapping",
+    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS",
+];
+
+/// Java extraction (public classes, interfaces, records, enums, public methods,
+/// JUnit tests, picocli commands and annotated HTTP endpoints)
+pub struct JavaExtractor;
+
+impl LanguageExtractor for JavaExtractor {
+    fn name(&self) -> &'static str {
+        "java"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["java"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        Some(tree_sitter_java::language())
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let mut capabilities = Vec::new();
+        if let Some(root) = file.root {
+            extract_matches(root, file.source, &mut capabilities)?;
+        }
+        Ok(capabilities)
+    }
+}
+
+fn extract_matches(root: Node, source: &str, capabilities: &mut Vec<Capability>) -> Result<()> {
+    let query = query::builtin(&QUERY, tree_sitter_java::language(), include_str!("queries/java.scm"));
+
+    for m in query::matches(query, root, source) {
+        let (kind, node, name) = match (m.definition(), m.name(source)) {
+            (Some((kind, node)), Some(name)) => (kind, node, name.to_string()),
+            _ => continue,
+        };
+        // Members of public interfaces are implicitly public
+        let in_interface = enclosing_types(&node)
+            .first()
+            .map(|t| t.kind() == "interface_declaration")
+            .unwrap_or(false);
+        let visibility = if has_modifier(&node, "public") || in_interface {
+            Visibility::Public
+        } else if has_modifier(&node, "protected") {
+            Visibility::Protected
+        } else if has_modifier(&node, "private") {
+            Visibility::Private
+        } else {
+            Visibility::Internal // package-private
+        };
+
+        let annotations = annotations(&node, source);
+        let has_annotation = |names: &[&str]| annotations.iter().any(|a| names.contains(&a.name.as_str()));
+        let (kind, name) = match kind {
+            // JUnit test methods are usually package-private
+            CapabilityKind::Function if has_annotation(&["Test", "ParameterizedTest", "RepeatedTest"]) => {
+                let mut cap = capability_from_node(name, CapabilityKind::Test, &node, source);
+                cap.visibility = visibility;
+                capabilities.push(cap);
+                continue;
+            }
+            // picocli: `@Command(name = "sync") class SyncCommand`
+            CapabilityKind::Class if has_annotation(&["Command"]) => {
+                let command = annotations
+                    .iter()
+                    .find(|a| a.name == "Command")
+                    .and_then(|a| command_name(&a.arguments))
+                    .unwrap_or(name);
+                (CapabilityKind::CliCommand, command)
+            }
+            kind => (kind, name),
+        };
+
+        let route = match kind {
+            CapabilityKind::Function => jvm_route(&annotations, &route_prefix(&node, source)),
+            _ => None,
+        };
+        let mut cap = match route {
+            Some((method, path)) => with_route(
+                capability_from_node(name, CapabilityKind::ApiRoute, &node, source),
+                &method,
+                &path,
+            ),
+            None if visibility == Visibility::Public => capability_from_node(name, kind, &node, source),
+            None => continue,
+        };
+        cap.visibility = visibility;
+        capabilities.push(cap);
+    }
+
+    Ok(())
+}
+
+/// `name = "sync"` argument of a picocli `@Command`
+fn command_name(arguments: &str) -> Option<String> {
+    let start = arguments.find("name")?;
+    let rest = arguments[start + "name".len()..].trim_start().strip_prefix('=')?.trim_start();
+    let quoted = rest.strip_prefix('"')?;
+    quoted.find('"').map(|end| quoted[..end].to_string())
+}
+
+/// Class, interface, record and enum declarations enclosing a node, innermost first
+fn enclosing_types<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    let mut types = Vec::new();
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if matches!(
+            parent.kind(),
+            "class_declaration" | "interface_declaration" | "record_declaration" | "enum_declaration"
+        ) {
+            types.push(parent);
+        }
+        current = parent.parent();
+    }
+    types
+}
+
+/// Class-level `@RequestMapping("/api")` / `@Path("/api")` prefixes of the
+/// enclosing types, outermost first
+fn route_prefix(node: &Node, source: &str) -> String {
+    enclosing_types(node).iter().rev().fold(String::new(), |prefix, declaration| {
+        match annotations(declaration, source)
+            .iter()
+            .find(|a| a.name == "RequestMapping" || a.name == "Path")
+            .and_then(|a| a.path.clone())
+        {
+            Some(path) => join_paths(&prefix, &path),
+            None => prefix,
+        }
+    })
+}
+
+/// The `modifiers` child of a declaration, if any
+fn modifiers<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .find(|c| c.kind() == "modifiers")
+}
+
+/// Whether a declaration carries a keyword modifier such as `public`
+fn has_modifier(node: &Node, keyword: &str) -> bool {
+    modifiers(node)
+        .map(|m| (0..m.child_count()).filter_map(|i| m.child(i)).any(|c| c.kind() == keyword))
+        .unwrap_or(false)
+}
+
+/// An annotation on a declaration, reduced to what route detection needs
+pub struct Annotation {
+    /// Simple name (`@org.x.GetMapping` -> `GetMapping`)
+    pub name: String,
+    /// First path-like argument: `("/x")`, `(value = "/x")` or `(path = "/x")`
+    pub path: Option<String>,
+    /// Raw argument text, e.g. `(value = "/x", method = RequestMethod.POST)`
+    pub arguments: String,
+}
+
+/// Annotations on a declaration
+fn annotations(node: &Node, source: &str) -> Vec<Annotation> {
+    let modifiers = match modifiers(node) {
+        Some(m) => m,
+        None => return Vec::new(),
+    };
+    (0..modifiers.child_count())
+        .filter_map(|i| modifiers.child(i))
+        .filter(|c| c.kind() == "annotation" || c.kind() == "marker_annotation")
+        .filter_map(|c| {
+            let name = c.child_by_field_name("name")?.utf8_text(source.as_bytes()).ok()?;
+            let arguments = c
+                .child_by_field_name("arguments")
+                .and_then(|a| a.utf8_text(source.as_bytes()).ok())
+                .unwrap_or("");
+            Some(Annotation {
+                name: name.rsplit('.').next().unwrap_or(name).to_string(),
+                path: annotation_path(arguments),
+                arguments: arguments.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// First string literal that is positional or bound to `value`/`path`
+pub fn annotation_path(arguments: &str) -> Option<String> {
+    let inner = arguments.trim().strip_prefix('(')?.strip_suffix(')')?;
+    inner.split(',').find_map(|arg| {
+        let (key, value) = match arg.split_once('=') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => ("value", arg.trim()),
+        };
+        let value = value.trim_start_matches('{').trim_end_matches('}').trim();
+        ((key == "value" || key == "path") && value.starts_with('"')).then(|| unquote(value))
+    })
+}
+
+/// HTTP method and full path of a Spring or JAX-RS endpoint, if the
+/// annotations declare one
+pub fn jvm_route(annotations: &[Annotation], prefix: &str) -> Option<(String, String)> {
+    let route = annotations
+        .iter()
+        .find(|a| JVM_ROUTE_ANNOTATIONS.contains(&a.name.as_str()))?;
+
+    let method = if route.name == "RequestMapping" {
+        route
+            .arguments
+            .split("RequestMethod.")
+            .nth(1)
+            .map(|m| m.chars().take_while(|c| c.is_alphabetic()).collect::<String>())
+            .unwrap_or_else(|| "ANY".to_string())
+    } else {
+        method_from_name(&route.name)?.to_string()
+    };
+
+    // JAX-RS puts the path in a separate @Path annotation
+    let path = route
+        .path
+        .clone()
+        .or_else(|| annotations.iter().find(|a| a.name == "Path").and_then(|a| a.path.clone()))
+        .unwrap_or_default();
+
+    Some((method, join_paths(prefix, &path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shredder::Shredder;
+    use std::path::Path;
+
+    #[test]
+    fn test_extracts_spring_endpoints() {
+        let source = r#"
+@RestController
+@RequestMapping("/api")
+public class OrderController {
+    @GetMapping("/orders/{id}")
+    Order find(@PathVariable long id) { return repo.find(id); }
+
+    public void reindex() {}
+
+    private void audit() {}
+}
+
+public interface OrderRepository {
+    Order find(long id);
+}
+
+public record Order(long id, String sku) {}
+"#;
+        let mut shredder = Shredder::new().unwrap();
+        let caps = shredder.shred_source(Path::new("OrderController.java"), source).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("OrderController", CapabilityKind::Class),
+                ("find", CapabilityKind::ApiRoute),
+                ("reindex", CapabilityKind::Function),
+                ("OrderRepository", CapabilityKind::Interface),
+                ("find", CapabilityKind::Function),
+                ("Order", CapabilityKind::Class),
+            ]
+        );
+        let route = caps[1].route.as_ref().unwrap();
+        assert_eq!((route.method.as_str(), route.path.as_str()), ("GET", "/api/orders/{id}"));
+    }
+}
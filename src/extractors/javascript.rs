@@ -0,0 +1,32 @@
+use anyhow::Result;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Query};
+
+use super::typescript::extract_script;
+use super::{query, LanguageExtractor, SourceFile};
+use crate::shredder::Capability;
+
+static QUERY: OnceLock<Query> = OnceLock::new();
+
+/// JavaScript extraction (JSX and ES/CommonJS modules included). Same
+/// capabilities as TypeScript, minus the TypeScript-only declarations.
+pub struct JavaScriptExtractor;
+
+impl LanguageExtractor for JavaScriptExtractor {
+    fn name(&self) -> &'static str {
+        "javascript"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["js", "jsx", "mjs", "cjs"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        Some(tree_sitter_javascript::language())
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let query = query::builtin(&QUERY, tree_sitter_javascript::language(), include_str!("queries/javascript.scm"));
+        extract_script(query, file)
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::Language;
+
+use super::java::{annotation_path, jvm_route, Annotation};
+use super::routes::with_route;
+use super::{
+    attribute_lines_before, brace_block_end, capability_from_span, literal_end, qualify_by_containment,
+    LanguageExtractor, SourceFile,
+};
+use crate::shredder::{Capability, CapabilityKind};
+
+/// Kotlin extraction (public classes, interfaces, enums, objects, functions,
+/// JUnit tests and Spring/JAX-RS annotated endpoints), from the source text.
+///
+/// Kotlin declarations are public unless marked otherwise.
+pub struct KotlinExtractor;
+
+impl LanguageExtractor for KotlinExtractor {
+    fn name(&self) -> &'static str {
+        "kotlin"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["kt"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        None
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let source = file.source;
+        let mut capabilities = Vec::new();
+        static ANNOTATION_REGEX: OnceLock<Regex> = OnceLock::new();
+        let annotation_regex =
+            ANNOTATION_REGEX.get_or_init(|| Regex::new(r"@([\w.]+)(\([^)\n]*\))?").expect("valid annotation regex"));
+        // (start, end, route prefix) of every public type, for nested endpoints
+        let mut type_spans: Vec<(usize, usize, String)> = Vec::new();
+
+        static TYPE_REGEX: OnceLock<Regex> = OnceLock::new();
+        let type_regex = TYPE_REGEX.get_or_init(|| {
+            Regex::new(
+                r"(?m)^[ \t]*((?:@\w+(?:\([^)\n]*\))?\s+)*)((?:[a-z]+[ \t]+)*)(class|interface|object)[ \t]+([A-Za-z_]\w*)",
+            )
+            .expect("valid type regex")
+        });
+        for caps in type_regex.captures_iter(source) {
+            if !is_public(&caps[2]) {
+                continue;
+            }
+            let start = caps.get(4).unwrap().start();
+            let decl_start = line_start(source, start);
+            let decl_end = declaration_end(source, caps.get(0).unwrap().end());
+            let prefix = parse_annotations(annotation_regex, &caps[1], source, start)
+                .into_iter()
+                .find(|a| a.name == "RequestMapping" || a.name == "Path")
+                .and_then(|a| a.path)
+                .unwrap_or_default();
+            type_spans.push((decl_start, decl_end, prefix));
+            let kind = if &caps[3] == "interface" {
+                CapabilityKind::Interface
+            } else if caps[2].split_whitespace().any(|m| m == "enum") {
+                CapabilityKind::Enum
+            } else {
+                CapabilityKind::Class
+            };
+            capabilities.push(capability_from_span(
+                caps[4].to_string(),
+                kind,
+                source,
+                decl_start,
+                decl_end,
+            ));
+        }
+
+        static FUN_REGEX: OnceLock<Regex> = OnceLock::new();
+        let fun_regex = FUN_REGEX.get_or_init(|| {
+            Regex::new(
+                r"(?m)^[ \t]*((?:@\w+(?:\([^)\n]*\))?\s+)*)((?:[a-z]+[ \t]+)*)fun[ \t]+(?:<[^>]*>[ \t]*)?(?:[\w.]+\.)?([A-Za-z_]\w*)[ \t]*\(",
+            )
+            .expect("valid fun regex")
+        });
+        for caps in fun_regex.captures_iter(source) {
+            if !is_public(&caps[2]) {
+                continue;
+            }
+            let name_match = caps.get(3).unwrap();
+            let annotations = parse_annotations(annotation_regex, &caps[1], source, name_match.start());
+            // Innermost enclosing type provides the route prefix
+            let prefix = type_spans
+                .iter()
+                .filter(|(start, end, _)| *start < name_match.start() && name_match.start() < *end)
+                .max_by_key(|(start, _, _)| *start)
+                .map(|(_, _, prefix)| prefix.as_str())
+                .unwrap_or("");
+
+            let capability = capability_from_span(
+                name_match.as_str().to_string(),
+                CapabilityKind::Function,
+                source,
+                line_start(source, name_match.start()),
+                // From the `(`, so the parameter list is tracked
+                declaration_end(source, caps.get(0).unwrap().end() - 1),
+            );
+            capabilities.push(match jvm_route(&annotations, prefix) {
+                Some((method, path)) => with_route(
+                    Capability {
+                        kind: CapabilityKind::ApiRoute,
+                        ..capability
+                    },
+                    &method,
+                    &path,
+                ),
+                None if annotations.iter().any(|a| matches!(a.name.as_str(), "Test" | "ParameterizedTest")) => {
+                    Capability {
+                        kind: CapabilityKind::Test,
+                        ..capability
+                    }
+                }
+                None => capability,
+            });
+        }
+
+        capabilities.sort_by_key(|c| c.line);
+        qualify_by_containment(&mut capabilities, ".");
+        Ok(capabilities)
+    }
+}
+
+fn is_public(modifiers: &str) -> bool {
+    !modifiers
+        .split_whitespace()
+        .any(|m| matches!(m, "private" | "protected" | "internal"))
+}
+
+/// Annotations written inline before a declaration or on the lines above it
+fn parse_annotations(regex: &Regex, inline: &str, source: &str, decl: usize) -> Vec<Annotation> {
+    let above = attribute_lines_before(source, decl, '@').join(" ");
+    regex
+        .captures_iter(&format!("{} {}", inline, above))
+        .map(|caps| {
+            let name = caps[1].rsplit('.').next().unwrap_or(&caps[1]).to_string();
+            let arguments = caps.get(2).map(|m| m.as_str()).unwrap_or("").to_string();
+            Annotation {
+                name,
+                path: annotation_path(&arguments),
+                arguments,
+            }
+        })
+        .collect()
+}
+
+/// Byte offset of the first non-blank character on the line containing `pos`
+fn line_start(source: &str, pos: usize) -> usize {
+    let start = source[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    start + (source[start..].len() - source[start..].trim_start().len())
+}
+
+/// End of a declaration whose header starts at `from`: a `{ }` body, the rest
+/// of the line for expression bodies (`= ...`), or the header line itself for
+/// abstract members and body-less classes. Default arguments' string and char
+/// literals are skipped.
+fn declaration_end(source: &str, from: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut depth = 0i32;
+    let mut i = from;
+    while i < bytes.len() {
+        let arrow = i > from && bytes[i - 1] == b'-'; // `->` in a function type
+        match bytes[i] {
+            b'"' | b'\'' => {
+                i = literal_end(source, i);
+                continue;
+            }
+            b'(' | b'<' => depth += 1,
+            b')' => depth -= 1,
+            b'>' if !arrow => depth -= 1,
+            b'{' if depth <= 0 => return brace_block_end(source, i),
+            b'=' if depth <= 0 => return source[i..].find('\n').map(|n| i + n).unwrap_or(source.len()),
+            b'\n' if depth <= 0 => {
+                // Headers may continue on the next line (`: Base`, `{`, `=`)
+                let rest = source[i..].trim_start();
+                if !(rest.starts_with('{') || rest.starts_with(':') || rest.starts_with('=')) {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    source.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_extracts_kotlin_declarations() {
+        let source = r#"
+@RestController
+@RequestMapping("/billing")
+class InvoiceController(private val service: InvoiceService) {
+    @PostMapping("/invoices")
+    fun create(@RequestBody req: InvoiceRequest): Invoice {
+        return service.create(req)
+    }
+
+    fun total(): Int = service.total()
+
+    private fun audit() {}
+}
+
+fun charge(amount: Int = 1, open: Char = '(', label: String = "{ $amount }"): Int {
+    log("} charged")
+    return amount
+}
+
+fun refund(
+    amount: Int,
+    onDone: (Int) -> Unit = {},
+): Int {
+    return -amount
+}
+
+internal object Registry
+"#;
+        let file = SourceFile {
+            path: Path::new("InvoiceController.kt"),
+            source,
+            root: None,
+        };
+        let caps = KotlinExtractor.extract(&file).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("InvoiceController", CapabilityKind::Class),
+                ("create", CapabilityKind::ApiRoute),
+                ("total", CapabilityKind::Function),
+                ("charge", CapabilityKind::Function),
+                ("refund", CapabilityKind::Function),
+            ]
+        );
+        assert_eq!(caps[2].code_snippet, "fun total(): Int = service.total()");
+        // Default arguments and multi-line parameter lists keep their bodies
+        // as do braces and parentheses in string and char literals
+        assert_eq!((caps[3].line, caps[3].end_line), (15, 18));
+        assert_eq!((caps[4].line, caps[4].end_line), (20, 25));
+        let route = caps[1].route.as_ref().unwrap();
+        assert_eq!((route.method.as_str(), route.path.as_str()), ("POST", "/billing/invoices"));
+    }
+}
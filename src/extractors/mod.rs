@@ -0,0 +1,473 @@
+use anyhow::Result;
+use std::path::Path;
+use tree_sitter::{Language, Node};
+
+use crate::shredder::{Capability, CapabilityKind, Visibility};
+
+mod csharp;
+mod go;
+mod java;
+mod javascript;
+mod kotlin;
+mod python;
+pub mod query;
+mod routes;
+mod ruby;
+mod rust;
+mod sfc;
+mod solidity;
+mod typescript;
+
+pub use csharp::CSharpExtractor;
+pub use go::GoExtractor;
+pub use java::JavaExtractor;
+pub use javascript::JavaScriptExtractor;
+pub use kotlin::KotlinExtractor;
+pub use python::PythonExtractor;
+pub use query::CustomQueries;
+pub use ruby::RubyExtractor;
+pub use rust::RustExtractor;
+pub use sfc::SfcExtractor;
+pub use solidity::SolidityExtractor;
+pub use typescript::{TsxExtractor, TypeScriptExtractor};
+
+/// A source file handed to a `LanguageExtractor`
+pub struct SourceFile<'a> {
+    pub path: &'a Path,
+    pub source: &'a str,
+    /// Syntax tree root, `None` when the extractor has no grammar
+    pub root: Option<Node<'a>>,
+}
+
+/// THE EXTRACTORS: Pluggable per-language capability extraction
+///
+/// Implement this trait to teach the Shredder a new language. The Shredder
+/// picks the extractor by file extension, parses the file with its grammar
+/// and hands the syntax tree over for extraction. Extractors without a
+/// tree-sitter grammar work directly on the source text.
+///
+/// The Solidity, Ruby, Kotlin and C# extractors are such source-level ones, by
+/// design: matching declarations and delimiting blocks by braces or `end`
+/// keeps them free of grammar crates that must track our tree-sitter version.
+pub trait LanguageExtractor: Send + Sync {
+    /// Language identifier (e.g. "typescript")
+    fn name(&self) -> &'static str;
+
+    /// File extensions handled by this extractor (without the leading dot)
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Tree-sitter grammar used to parse files of this language
+    /// (`None` for source-level extractors)
+    fn grammar(&self) -> Option<Language>;
+
+    /// Extract capabilities from a parsed file
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>>;
+}
+
+/// Registry of language extractors, looked up by file extension
+pub struct ExtractorRegistry {
+    extractors: Vec<Box<dyn LanguageExtractor>>,
+}
+
+impl ExtractorRegistry {
+    /// Create an empty registry (no languages supported)
+    pub fn empty() -> Self {
+        Self {
+            extractors: Vec::new(),
+        }
+    }
+
+    /// Register an extractor. Later registrations take precedence, so a
+    /// custom extractor can override a built-in one for the same extension.
+    pub fn register(&mut self, extractor: Box<dyn LanguageExtractor>) {
+        self.extractors.push(extractor);
+    }
+
+    /// Find the extractor responsible for a file extension
+    pub fn for_extension(&self, ext: &str) -> Option<&dyn LanguageExtractor> {
+        self.extractors
+            .iter()
+            .rev()
+            .find(|e| e.extensions().contains(&ext))
+            .map(|e| e.as_ref())
+    }
+
+    /// Find an extractor by language name (e.g. "typescript")
+    pub fn by_name(&self, name: &str) -> Option<&dyn LanguageExtractor> {
+        self.extractors
+            .iter()
+            .rev()
+            .find(|e| e.name() == name)
+            .map(|e| e.as_ref())
+    }
+
+    /// Names of all registered languages
+    #[allow(dead_code)]
+    pub fn languages(&self) -> Vec<&'static str> {
+        self.extractors.iter().map(|e| e.name()).collect()
+    }
+}
+
+impl Default for ExtractorRegistry {
+    /// Registry with all built-in extractors
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Box::new(TypeScriptExtractor));
+        registry.register(Box::new(TsxExtractor));
+        registry.register(Box::new(JavaScriptExtractor));
+        registry.register(Box::new(SfcExtractor));
+        registry.register(Box::new(RustExtractor));
+        registry.register(Box::new(PythonExtractor));
+        registry.register(Box::new(GoExtractor));
+        registry.register(Box::new(SolidityExtractor));
+        registry.register(Box::new(RubyExtractor));
+        registry.register(Box::new(JavaExtractor));
+        registry.register(Box::new(KotlinExtractor));
+        registry.register(Box::new(CSharpExtractor));
+        registry
+    }
+}
+
+/// Build a capability from a syntax node (authorship is filled in by the Shredder)
+pub fn capability_from_node(
+    name: String,
+    kind: CapabilityKind,
+    node: &Node,
+    source: &str,
+) -> Capability {
+    Capability {
+        qualified_name: qualified_name(&name, node, source),
+        name,
+        kind,
+        visibility: Visibility::Public,
+        line: node.start_position().row + 1,
+        end_line: line_at(source, last_byte(source, node.start_byte(), node.end_byte())),
+        byte_range: (node.start_byte(), node.end_byte()),
+        signature: signature_at(source, node.start_byte()),
+        doc_comment: docstring(node, source).or_else(|| doc_comment_before(source, node.start_byte())),
+        code_snippet: code_snippet(node, source),
+        authorship: None,
+        route: None,
+        tests: Vec::new(),
+        calls: Vec::new(),
+        references: Vec::new(),
+        metrics: Default::default(),
+        cell: None,
+    }
+}
+
+/// Build a capability from a byte range of the source (for extractors without a grammar)
+pub fn capability_from_span(
+    name: String,
+    kind: CapabilityKind,
+    source: &str,
+    start_byte: usize,
+    end_byte: usize,
+) -> Capability {
+    Capability {
+        qualified_name: name.clone(),
+        name,
+        kind,
+        visibility: Visibility::Public,
+        line: line_at(source, start_byte),
+        end_line: line_at(source, last_byte(source, start_byte, end_byte)),
+        byte_range: (start_byte, end_byte),
+        signature: signature_at(source, start_byte),
+        doc_comment: doc_comment_before(source, start_byte),
+        code_snippet: truncate_snippet(&source[start_byte..end_byte]),
+        authorship: None,
+        route: None,
+        tests: Vec::new(),
+        calls: Vec::new(),
+        references: Vec::new(),
+        metrics: Default::default(),
+        cell: None,
+    }
+}
+
+/// Qualify capabilities by the class, interface, enum, module and schema
+/// capabilities whose spans enclose them (for extractors without a syntax
+/// tree), e.g. `Vault.withdraw`
+pub fn qualify_by_containment(capabilities: &mut [Capability], separator: &str) {
+    let containers: Vec<(usize, usize, String)> = capabilities
+        .iter()
+        .filter(|c| {
+            matches!(
+                c.kind,
+                CapabilityKind::Class
+                    | CapabilityKind::Interface
+                    | CapabilityKind::Enum
+                    | CapabilityKind::Module
+                    | CapabilityKind::Schema
+            )
+        })
+        .map(|c| (c.byte_range.0, c.byte_range.1, c.name.clone()))
+        .collect();
+
+    for cap in capabilities.iter_mut() {
+        let (start, end) = cap.byte_range;
+        let mut enclosing: Vec<&(usize, usize, String)> = containers
+            .iter()
+            .filter(|(s, e, _)| *s <= start && end <= *e && (*s, *e) != (start, end))
+            .collect();
+        enclosing.sort_by_key(|(s, _, _)| *s);
+        let mut path: Vec<&str> = enclosing.iter().map(|(_, _, name)| name.as_str()).collect();
+        path.push(&cap.name);
+        cap.qualified_name = path.join(separator);
+    }
+}
+
+/// Name of a capability prefixed with its enclosing classes, modules and impl
+/// blocks (`Matchmaker::match_need` in Rust, `OrderController.find` elsewhere)
+fn qualified_name(name: &str, node: &Node, source: &str) -> String {
+    let text = |n: Node| n.utf8_text(source.as_bytes()).unwrap_or("").to_string();
+    let mut path = Vec::new();
+    let mut rust_scope = false;
+
+    // Go methods belong to their receiver type
+    if node.kind() == "method_declaration" {
+        if let Some(receiver) = node.child_by_field_name("receiver") {
+            if let Some(type_name) = first_descendant(receiver, "type_identifier") {
+                path.push(text(type_name));
+            }
+        }
+    }
+
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        let container = match ancestor.kind() {
+            "impl_item" => {
+                rust_scope = true;
+                ancestor.child_by_field_name("type")
+            }
+            "mod_item" | "trait_item" => {
+                rust_scope = true;
+                ancestor.child_by_field_name("name")
+            }
+            "class_declaration" | "class_definition" | "abstract_class_declaration"
+            | "interface_declaration" | "record_declaration" | "enum_declaration"
+            | "internal_module" => {
+                ancestor.child_by_field_name("name")
+            }
+            _ => None,
+        };
+        if let Some(container) = container {
+            // `impl<T> Cache<T>` qualifies as `Cache`
+            let container = text(container);
+            path.insert(0, container.split('<').next().unwrap_or("").trim().to_string());
+        }
+        current = ancestor.parent();
+    }
+
+    path.push(name.to_string());
+    path.join(if rust_scope { "::" } else { "." })
+}
+
+fn first_descendant<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .find_map(|child| first_descendant(child, kind))
+}
+
+/// Offset of the last character of a non-empty span, so that a block ending
+/// in a newline is not counted as ending on the following line
+fn last_byte(source: &str, start: usize, end: usize) -> usize {
+    if end > start && source[..end].ends_with('\n') {
+        end - 1
+    } else {
+        end
+    }
+}
+
+/// Declaration header starting at `start`, on one line: decorator/annotation
+/// lines are skipped and the header ends at the body (`{`, `;` or a line break
+/// outside parentheses), e.g. `pub fn match_need(&self, need: &Need) -> Vec<Match>`
+pub fn signature_at(source: &str, start: usize) -> String {
+    let mut from = start;
+    loop {
+        let rest = &source[from..];
+        let line_end = match rest.find('\n') {
+            Some(i) => i,
+            None => break,
+        };
+        let line = rest[..line_end].trim();
+        if !(line.starts_with('@') || line.starts_with("#[")) {
+            break;
+        }
+        let next = &rest[line_end + 1..];
+        from += line_end + 1 + (next.len() - next.trim_start().len());
+    }
+
+    let mut depth = 0i32;
+    let mut end = source.len();
+    for (i, c) in source[from..].char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            '{' | ';' | '\n' if depth <= 0 => {
+                end = from + i;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let header = source[from..end].split_whitespace().collect::<Vec<_>>().join(" ");
+    // `const f = async () => {` and Python's `def f():`
+    header
+        .trim_end_matches("=>")
+        .trim_end()
+        .trim_end_matches(':')
+        .to_string()
+}
+
+/// Comment block directly above the line containing `start` (`///`, `//`,
+/// `/** */`, `#`), skipping attribute lines in between, with comment markers
+/// stripped
+pub fn doc_comment_before(source: &str, start: usize) -> Option<String> {
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let mut lines: Vec<&str> = Vec::new();
+    for line in source[..line_start].lines().rev().map(|l| l.trim()) {
+        if line.starts_with("#[") || line.starts_with('@') || (line.starts_with('[') && line.ends_with(']')) {
+            if lines.is_empty() {
+                continue; // Attributes between the comment and the declaration
+            }
+            break;
+        }
+        let is_comment = line.starts_with("//")
+            || line.starts_with("/*")
+            || line.starts_with('*')
+            || line.starts_with('#');
+        if !is_comment {
+            break;
+        }
+        lines.push(line);
+    }
+    lines.reverse();
+
+    let text: Vec<&str> = lines
+        .iter()
+        .map(|l| {
+            let l = l.strip_suffix("*/").unwrap_or(l);
+            ["///", "//!", "//", "/**", "/*", "*", "#"]
+                .iter()
+                .find_map(|marker| l.strip_prefix(marker))
+                .unwrap_or(l)
+                .trim()
+        })
+        .collect();
+    let text = text.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Python docstring: a string literal as the first statement of a function or class body
+fn docstring(node: &Node, source: &str) -> Option<String> {
+    let definition = match node.kind() {
+        "decorated_definition" => node.child_by_field_name("definition")?,
+        "function_definition" | "class_definition" => *node,
+        _ => return None,
+    };
+    let statement = definition.child_by_field_name("body")?.named_child(0)?;
+    let string = statement.named_child(0).filter(|s| {
+        statement.kind() == "expression_statement" && s.kind() == "string"
+    })?;
+    let text = string.utf8_text(source.as_bytes()).ok()?;
+    let text = text
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        .trim_matches(|c| c == '"' || c == '\'');
+    let lines: Vec<&str> = text.lines().map(|l| l.trim()).collect();
+    let text = lines.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Extract code snippet from a node (limited to 500 chars for embedding)
+pub fn code_snippet(node: &Node, source: &str) -> String {
+    truncate_snippet(&source[node.start_byte()..node.end_byte()])
+}
+
+fn truncate_snippet(snippet: &str) -> String {
+    // Limit to 500 chars, but try to keep it readable
+    if snippet.len() > 500 {
+        snippet.chars().take(500).collect::<String>() + "..."
+    } else {
+        snippet.to_string()
+    }
+}
+
+/// 1-based line number of a byte offset
+pub fn line_at(source: &str, byte: usize) -> usize {
+    source[..byte].matches('\n').count() + 1
+}
+
+/// Byte offset just past the `{ ... }` block opening at or after `from`.
+/// Returns the offset past the first `;` instead if it comes before any `{`
+/// (declarations without a body), or the end of the source if unbalanced.
+/// Braces and semicolons in string and char literals don't count.
+pub fn brace_block_end(source: &str, from: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut depth = 0usize;
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' => {
+                i = literal_end(source, i);
+                continue;
+            }
+            b';' if depth == 0 => return i + 1,
+            b'{' => depth += 1,
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    source.len()
+}
+
+/// Byte offset just past the string or char literal opening at `start` (a
+/// `"`, `'` or `"""`). A quoted literal left open ends with its line, so a
+/// stray apostrophe (`// don't`) skips no further.
+pub fn literal_end(source: &str, start: usize) -> usize {
+    let rest = &source[start..];
+    if let Some(raw) = rest.strip_prefix("\"\"\"") {
+        return raw.find("\"\"\"").map(|i| start + 3 + i + 3).unwrap_or(source.len());
+    }
+    let quote = rest.as_bytes()[0];
+    let mut escaped = false;
+    for (i, b) in rest.bytes().enumerate().skip(1) {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'\n' => return start + i,
+            _ if b == quote => return start + i + 1,
+            _ => {}
+        }
+    }
+    source.len()
+}
+
+/// Attribute/annotation lines directly above the line containing `start`
+/// (e.g. `@GetMapping("/x")` or `[HttpGet]`), nearest first. Comment lines
+/// are skipped; any other line ends the run.
+pub fn attribute_lines_before(source: &str, start: usize, marker: char) -> Vec<&str> {
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    source[..line_start]
+        .lines()
+        .rev()
+        .map(|l| l.trim())
+        .filter(|l| !l.starts_with("//"))
+        .take_while(|l| l.starts_with(marker))
+        .collect()
+}
+
+/// PascalCase names are treated as components / exported identifiers
+pub fn starts_uppercase(name: &str) -> bool {
+    name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false)
+}
@@ -0,0 +1,213 @@
+use anyhow::Result;
+use std::path::Path;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Node, Query};
+
+use super::routes::{method_from_name, unquote, with_route};
+use super::{capability_from_node, query, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind, Visibility};
+
+static QUERY: OnceLock<Query> = OnceLock::new();
+
+/// Python extraction (functions, classes, enums, protocols, routes, CLI
+/// commands and tests)
+pub struct PythonExtractor;
+
+impl LanguageExtractor for PythonExtractor {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["py"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        Some(tree_sitter_python::language())
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let mut capabilities = Vec::new();
+        if let Some(root) = file.root {
+            extract_matches(root, file.source, file.path, &mut capabilities)?;
+        }
+        Ok(capabilities)
+    }
+}
+
+fn extract_matches(root: Node, source: &str, path: &Path, capabilities: &mut Vec<Capability>) -> Result<()> {
+    let query = query::builtin(&QUERY, tree_sitter_python::language(), include_str!("queries/python.scm"));
+
+    for m in query::matches(query, root, source) {
+        let (kind, node, name) = match (m.definition(), m.name(source)) {
+            (Some((kind, node)), Some(name)) => (kind, node, name.to_string()),
+            _ => continue,
+        };
+        if kind == CapabilityKind::CliCommand {
+            capabilities.push(capability_from_node(name, kind, &node, source));
+            continue;
+        }
+        match decorator_route(&node, source)? {
+            // Include the decorator in the snippet
+            Some((method, path)) => capabilities.push(with_route(
+                capability_from_node(name, CapabilityKind::ApiRoute, &node.parent().unwrap(), source),
+                &method,
+                &path,
+            )),
+            None => {
+                let kind = refine_kind(kind, &name, &node, source, path)?;
+                // Decorated commands include their decorators in the snippet
+                let node = match node.parent() {
+                    Some(p) if kind == CapabilityKind::CliCommand => p,
+                    _ => node,
+                };
+                let mut cap = capability_from_node(name, kind, &node, source);
+                // `_helper` is private by convention; dunder methods are not
+                if cap.name.starts_with('_') && !cap.name.ends_with("__") {
+                    cap.visibility = Visibility::Private;
+                }
+                capabilities.push(cap);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Tests, CLI commands, enums and interfaces among plain functions and classes
+fn refine_kind(kind: CapabilityKind, name: &str, node: &Node, source: &str, path: &Path) -> Result<CapabilityKind> {
+    if kind == CapabilityKind::Class {
+        let bases = match node.child_by_field_name("superclasses") {
+            Some(b) => b.utf8_text(source.as_bytes())?,
+            None => return Ok(kind),
+        };
+        let bases: Vec<&str> = bases
+            .trim_matches(|c| c == '(' || c == ')')
+            .split(',')
+            .map(|b| b.trim().rsplit('.').next().unwrap_or(""))
+            .collect();
+        return Ok(if bases.iter().any(|b| matches!(*b, "Enum" | "IntEnum" | "StrEnum" | "Flag" | "IntFlag")) {
+            CapabilityKind::Enum
+        } else if bases.iter().any(|b| matches!(*b, "Protocol" | "ABC")) {
+            CapabilityKind::Interface
+        } else {
+            kind
+        });
+    }
+
+    if is_test(name, node, source, path) {
+        return Ok(CapabilityKind::Test);
+    }
+    // click/typer: `@click.command()`, `@cli.command("x")`, `@app.command()`, `@click.group()`
+    if let Some(parent) = node.parent().filter(|p| p.kind() == "decorated_definition") {
+        for i in 0..parent.named_child_count() {
+            let decorator = parent.named_child(i).unwrap();
+            if decorator.kind() != "decorator" {
+                continue;
+            }
+            let text = decorator.utf8_text(source.as_bytes())?;
+            let callee = text.trim_start_matches('@').split('(').next().unwrap_or("");
+            if matches!(callee.rsplit('.').next(), Some("command" | "group")) && callee.contains('.') {
+                return Ok(CapabilityKind::CliCommand);
+            }
+        }
+    }
+    Ok(kind)
+}
+
+/// pytest's collection rules: `test` and `test_*` functions in `test_*.py` or
+/// `*_test.py` files, or methods of `Test*` classes
+fn is_test(name: &str, node: &Node, source: &str, path: &Path) -> bool {
+    if name != "test" && !name.starts_with("test_") {
+        return false;
+    }
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    if stem.starts_with("test_") || stem.ends_with("_test") {
+        return true;
+    }
+    let mut ancestor = node.parent();
+    while let Some(parent) = ancestor {
+        match parent.kind() {
+            "class_definition" => {
+                return parent
+                    .child_by_field_name("name")
+                    .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+                    .map(|class| class.starts_with("Test"))
+                    .unwrap_or(false)
+            }
+            "function_definition" => return false,
+            _ => ancestor = parent.parent(),
+        }
+    }
+    false
+}
+
+/// FastAPI/Flask route decorators on a function: `@app.get("/items/{id}")`,
+/// `@router.post(...)`, `@bp.route("/x", methods=["GET", "POST"])`
+fn decorator_route(node: &Node, source: &str) -> Result<Option<(String, String)>> {
+    let parent = match node.parent() {
+        Some(p) if node.kind() == "function_definition" && p.kind() == "decorated_definition" => p,
+        _ => return Ok(None),
+    };
+
+    for i in 0..parent.named_child_count() {
+        let decorator = parent.named_child(i).unwrap();
+        if decorator.kind() != "decorator" {
+            continue;
+        }
+        let call = match decorator.named_child(0) {
+            Some(c) if c.kind() == "call" => c,
+            _ => continue,
+        };
+        let attribute = match call.child_by_field_name("function") {
+            Some(f) if f.kind() == "attribute" => f,
+            _ => continue,
+        };
+        let verb = match attribute.child_by_field_name("attribute") {
+            Some(a) => a.utf8_text(source.as_bytes())?,
+            None => continue,
+        };
+        let args = match call.child_by_field_name("arguments") {
+            Some(a) => a,
+            None => continue,
+        };
+
+        let mut path = None;
+        let mut methods = Vec::new();
+        for j in 0..args.named_child_count() {
+            let arg = args.named_child(j).unwrap();
+            match arg.kind() {
+                "string" if path.is_none() => path = Some(unquote(arg.utf8_text(source.as_bytes())?)),
+                "keyword_argument" => {
+                    let key = arg.child_by_field_name("name").map(|n| n.utf8_text(source.as_bytes())).transpose()?;
+                    if key == Some("methods") {
+                        if let Some(list) = arg.child_by_field_name("value") {
+                            for k in 0..list.named_child_count() {
+                                let item = list.named_child(k).unwrap();
+                                methods.push(unquote(item.utf8_text(source.as_bytes())?).to_uppercase());
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let path = match path {
+            Some(p) if p.starts_with('/') => p,
+            _ => continue,
+        };
+        let method = match verb {
+            // Flask defaults to GET when no methods are given
+            "route" | "api_route" if methods.is_empty() => "GET".to_string(),
+            "route" | "api_route" => methods.join(","),
+            other => match method_from_name(other) {
+                Some(m) => m.to_string(),
+                None => continue,
+            },
+        };
+        return Ok(Some((method, path)));
+    }
+
+    Ok(None)
+}
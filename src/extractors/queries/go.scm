@@ -0,0 +1,36 @@
+; Capability captures: the definition node is tagged with its kind
+; (@function, @class, @component, @api_route) and its name with @name.
+; Only exported (capitalised) identifiers are capabilities.
+
+((function_declaration
+   name: (identifier) @name) @function
+ (#match? @name "^[A-Z]"))
+
+((method_declaration
+   name: (field_identifier) @name) @function
+ (#match? @name "^[A-Z]"))
+
+; Interface types are reported as @interface by the extractor
+((type_declaration
+   (type_spec
+     name: (type_identifier) @name
+     type: (_) @type)) @class
+ (#match? @name "^[A-Z]"))
+
+; cobra/urfave-cli commands: `&cobra.Command{Use: "serve [flags]", ...}`
+((composite_literal
+   type: (qualified_type
+     name: (type_identifier) @cli.type)
+   body: (literal_value)) @cli_command
+ (#eq? @cli.type "Command"))
+
+; Gin/Echo/chi/net/http registrations, validated by the extractor:
+; `r.GET("/users/:id", h)`, `r.Get(...)`, `mux.HandleFunc("/users", h)`
+(call_expression
+  function: (selector_expression
+    field: (field_identifier) @route.verb)
+  arguments: (argument_list
+    .
+    [(interpreted_string_literal) (raw_string_literal)] @route.path
+    .
+    (_))) @route.call
@@ -0,0 +1,18 @@
+; Capability captures: the definition node is tagged with its kind
+; (@function, @class, @interface, @enum) and its name with @name.
+; Visibility, tests, picocli commands and Spring/JAX-RS routes are resolved
+; by the extractor.
+
+([(class_declaration
+    name: (identifier) @name)
+  (record_declaration
+    name: (identifier) @name)] @class)
+
+(interface_declaration
+  name: (identifier) @name) @interface
+
+(enum_declaration
+  name: (identifier) @name) @enum
+
+(method_declaration
+  name: (identifier) @name) @function
@@ -0,0 +1,20 @@
+; Capability captures: the definition node is tagged with its kind
+; (@function, @class, @component, @api_route) and its name with @name.
+; The extractor refines these: route decorators make @api_route, click/typer
+; decorators @cli_command, pytest-style tests @test, Enum subclasses @enum and
+; Protocol/ABC subclasses @interface.
+
+(function_definition
+  name: (identifier) @name) @function
+
+(class_definition
+  name: (identifier) @name) @class
+
+; argparse subcommands: `subparsers.add_parser("serve")`
+((call
+   function: (attribute
+     attribute: (identifier) @cli.fn)
+   arguments: (argument_list
+     .
+     (string) @name)) @cli_command
+ (#eq? @cli.fn "add_parser"))
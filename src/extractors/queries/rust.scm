@@ -0,0 +1,35 @@
+; Capability captures: the definition node is tagged with its kind
+; (@function, @class, @component, @api_route) and its name with @name.
+
+; Public functions; private ones are only reported when an Actix/Rocket
+; route attribute makes them a handler, or `#[test]` makes them a test
+(function_item
+  (visibility_modifier)? @visibility
+  name: (identifier) @name) @function
+
+(struct_item
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name) @class
+
+(enum_item
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name) @enum
+
+(trait_item
+  (visibility_modifier)? @visibility
+  name: (type_identifier) @name) @interface
+
+; Inline modules (`mod x;` declarations have no body)
+(mod_item
+  (visibility_modifier)? @visibility
+  name: (identifier) @name
+  body: (declaration_list)) @module
+
+; Axum/Actix router registrations: `.route("/users", get(list).post(create))`
+((call_expression
+   function: (field_expression
+     field: (field_identifier) @route.verb)
+   arguments: (arguments
+     .
+     (string_literal) @route.path)) @route.call
+ (#eq? @route.verb "route"))
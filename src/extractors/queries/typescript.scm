@@ -0,0 +1,73 @@
+; Capability captures: the definition node is tagged with its kind
+; (@function, @class, @interface, @enum, @module, @test, @cli_command) and
+; its name with @name; @route.call marks route registrations, which the
+; extractor turns into api_route capabilities. PascalCase functions are
+; reported as React components by the extractor.
+
+(function_declaration
+  name: (identifier) @name) @function
+
+(class_declaration
+  name: (type_identifier) @name) @class
+
+(interface_declaration
+  name: (type_identifier) @name) @interface
+
+(enum_declaration
+  name: (identifier) @name) @enum
+
+; `namespace Billing {}` and `declare module "x" {}`
+(internal_module
+  name: (_) @name) @module
+
+(module
+  name: (_) @name) @module
+
+; Module-level `const handler = () => {}`; arrow functions nested in
+; function bodies or call arguments are implementation details
+(program
+  [(lexical_declaration
+     (variable_declarator
+       name: (identifier) @name
+       value: [(arrow_function) (function_expression)]) @function)
+   (variable_declaration
+     (variable_declarator
+       name: (identifier) @name
+       value: [(arrow_function) (function_expression)]) @function)])
+
+(export_statement
+  (lexical_declaration
+    (variable_declarator
+      name: (identifier) @name
+      value: [(arrow_function) (function_expression)]) @function))
+
+; Class methods (a method named `get` is no route: routes are registered
+; calls and framework handlers, below)
+((method_definition
+   name: (property_identifier) @name) @function
+ (#not-eq? @name "constructor"))
+
+; Jest/Mocha/Vitest cases: `it('creates a user', ...)`, `test(...)`
+((call_expression
+   function: (identifier) @test.fn
+   arguments: (arguments
+     .
+     [(string) (template_string)] @name)) @test
+ (#match? @test.fn "^(it|test)$"))
+
+; commander/yargs subcommands: `program.command('deploy <env>')`
+((call_expression
+   function: (member_expression
+     property: (property_identifier) @cli.fn)
+   arguments: (arguments
+     .
+     (string) @name)) @cli_command
+ (#eq? @cli.fn "command"))
+
+; Express/Fastify/Koa registrations, validated by the extractor:
+; `app.get('/users/:id', handler)`, `fastify.route({ method, url, handler })`
+((call_expression
+   function: (member_expression
+     property: (property_identifier) @route.verb)
+   arguments: (arguments)) @route.call
+ (#match? @route.verb "^(get|post|put|delete|patch|head|options|all|route)$"))
@@ -0,0 +1,173 @@
+//! THE QUERIES: Declarative tree-sitter capture patterns
+//!
+//! Queries tag the definition node with the capability kind (`@function`,
+//! `@class`, `@component`, `@api_route`, `@interface`, `@enum`,
+//! `@cli_command`, `@test`, `@module`) and its name with `@name`:
+//!
+//! ```scheme
+//! (function_declaration name: (identifier) @name) @function
+//! ```
+//!
+//! Built-in extractors may add their own captures (e.g. `@route.call`) and
+//! post-process matches; user queries only use the conventional ones.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tree_sitter::{Language, Node, Query, QueryCursor};
+
+use super::{capability_from_node, ExtractorRegistry};
+use crate::shredder::{Capability, CapabilityKind};
+
+/// Compile a built-in query once per process
+pub fn builtin(cell: &'static OnceLock<Query>, language: Language, source: &str) -> &'static Query {
+    cell.get_or_init(|| Query::new(language, source).expect("built-in query is valid"))
+}
+
+/// One query match: capture names and the nodes they matched
+pub struct QueryMatch<'q, 'tree> {
+    captures: Vec<(&'q str, Node<'tree>)>,
+}
+
+impl<'q, 'tree> QueryMatch<'q, 'tree> {
+    /// Node captured as `@<capture>`
+    pub fn node(&self, capture: &str) -> Option<Node<'tree>> {
+        self.captures
+            .iter()
+            .find(|(name, _)| *name == capture)
+            .map(|(_, node)| *node)
+    }
+
+    /// Source text captured as `@<capture>`
+    pub fn text<'s>(&self, capture: &str, source: &'s str) -> Option<&'s str> {
+        self.node(capture)
+            .map(|node| &source[node.start_byte()..node.end_byte()])
+    }
+
+    /// The `@name` capture; names captured from string literals drop their quotes
+    pub fn name<'s>(&self, source: &'s str) -> Option<&'s str> {
+        self.text("name", source)
+            .map(|name| name.trim_matches(|c| c == '"' || c == '\'' || c == '`'))
+    }
+
+    /// Definition node and kind, from the first capture named after a capability kind
+    pub fn definition(&self) -> Option<(CapabilityKind, Node<'tree>)> {
+        self.captures
+            .iter()
+            .find_map(|(name, node)| CapabilityKind::from_name(name).map(|kind| (kind, *node)))
+    }
+}
+
+/// Run a query over a syntax tree, in document order
+pub fn matches<'q, 'tree>(query: &'q Query, root: Node<'tree>, source: &'tree str) -> Vec<QueryMatch<'q, 'tree>> {
+    let names = query.capture_names();
+    let mut cursor = QueryCursor::new();
+    cursor
+        .matches(query, root, source.as_bytes())
+        .map(|m| QueryMatch {
+            captures: m
+                .captures
+                .iter()
+                .map(|c| (names[c.index as usize].as_str(), c.node))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Capabilities from the conventional `@<kind>` + `@name` captures
+pub fn capabilities(query: &Query, root: Node, source: &str) -> Vec<Capability> {
+    matches(query, root, source)
+        .iter()
+        .filter_map(|m| {
+            let (kind, node) = m.definition()?;
+            let name = m.name(source)?;
+            Some(capability_from_node(name.to_string(), kind, &node, source))
+        })
+        .collect()
+}
+
+/// User-supplied queries, keyed by language (`typescript.scm` extends the
+/// "typescript" extractor). Cheap to clone and share between threads.
+#[derive(Clone, Default)]
+pub struct CustomQueries {
+    queries: Arc<Vec<(String, Query)>>,
+}
+
+impl CustomQueries {
+    /// Load every `<language>.scm` file in `dir`, compiled against that
+    /// language's grammar
+    pub fn load_dir(dir: &Path, registry: &ExtractorRegistry) -> Result<Self> {
+        let mut queries = Vec::new();
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read query directory {}", dir.display()))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("scm"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let language = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            let grammar = registry
+                .by_name(language)
+                .and_then(|e| e.grammar())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "{}: no tree-sitter language named '{}'",
+                        path.display(),
+                        language
+                    )
+                })?;
+            let source = fs::read_to_string(&path)?;
+            let query = Query::new(grammar, &source)
+                .with_context(|| format!("Invalid query in {}", path.display()))?;
+            queries.push((language.to_string(), query));
+        }
+
+        Ok(Self {
+            queries: Arc::new(queries),
+        })
+    }
+
+    /// Queries extending a language
+    pub fn for_language<'a>(&'a self, language: &'a str) -> impl Iterator<Item = &'a Query> + 'a {
+        self.queries
+            .iter()
+            .filter(move |(name, _)| name == language)
+            .map(|(_, query)| query)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    #[test]
+    fn test_conventional_captures() {
+        let source = "def handler():\n    pass\n\nclass Worker:\n    pass\n";
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_python::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let query = Query::new(
+            tree_sitter_python::language(),
+            "(class_definition name: (identifier) @name) @class",
+        )
+        .unwrap();
+
+        let caps = capabilities(&query, tree.root_node(), source);
+        assert_eq!(caps.len(), 1);
+        assert_eq!(caps[0].name, "Worker");
+        assert_eq!(caps[0].kind, CapabilityKind::Class);
+        assert_eq!(caps[0].line, 4);
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::{Component, Path};
+
+use crate::shredder::{Capability, HttpRoute};
+
+/// HTTP method for a router method or annotation name
+/// (`app.get`, `r.GET`, `router.Post`, `@GetMapping` -> "GET")
+pub fn method_from_name(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    let verb = name
+        .strip_suffix("mapping")
+        .or_else(|| name.strip_prefix("http"))
+        .unwrap_or(&name);
+    match verb {
+        "get" => Some("GET"),
+        "post" => Some("POST"),
+        "put" => Some("PUT"),
+        "delete" => Some("DELETE"),
+        "patch" => Some("PATCH"),
+        "head" => Some("HEAD"),
+        "options" => Some("OPTIONS"),
+        "all" | "any" | "request" => Some("ANY"),
+        _ => None,
+    }
+}
+
+/// Strip quotes (and Python string prefixes like `r"..."`) from a string literal
+pub fn unquote(literal: &str) -> String {
+    let literal = literal.trim();
+    let literal = literal.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    literal
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+/// Join a controller-level prefix and a method-level path into "/a/b"
+pub fn join_paths(prefix: &str, path: &str) -> String {
+    let joined: Vec<&str> = prefix
+        .split('/')
+        .chain(path.split('/'))
+        .filter(|s| !s.is_empty())
+        .collect();
+    format!("/{}", joined.join("/"))
+}
+
+/// Attach an HTTP route to a capability
+pub fn with_route(mut capability: Capability, method: &str, path: &str) -> Capability {
+    capability.route = Some(HttpRoute {
+        method: method.to_string(),
+        path: path.to_string(),
+    });
+    capability
+}
+
+/// Route path of a Next.js App Router handler (`app/api/users/[id]/route.ts`
+/// -> `/api/users/[id]`). Route groups `(name)` and `@slot` folders are not
+/// part of the URL.
+pub fn next_app_route_path(path: &Path) -> Option<String> {
+    if path.file_stem()?.to_str()? != "route" {
+        return None;
+    }
+    let segments = segments_after(path.parent()?, "app")?;
+    let url: Vec<&str> = segments
+        .into_iter()
+        .filter(|s| !(s.starts_with('@') || (s.starts_with('(') && s.ends_with(')'))))
+        .collect();
+    Some(format!("/{}", url.join("/")))
+}
+
+/// Route path of a Next.js Pages Router API handler
+/// (`pages/api/users/[id].ts` -> `/api/users/[id]`)
+pub fn next_pages_api_path(path: &Path) -> Option<String> {
+    let mut segments = segments_after(path.parent()?, "pages")?;
+    if segments.first() != Some(&"api") {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    if stem != "index" {
+        segments.push(stem);
+    }
+    Some(format!("/{}", segments.join("/")))
+}
+
+/// Path components after the first component named `marker`
+fn segments_after<'a>(dir: &'a Path, marker: &str) -> Option<Vec<&'a str>> {
+    let components: Vec<&str> = dir
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => s.to_str(),
+            _ => None,
+        })
+        .collect();
+    let idx = components.iter().position(|c| *c == marker)?;
+    Some(components[idx + 1..].to_vec())
+}
+
+/// Conventional Rails resource route for a controller action
+/// (`UsersController#show` -> `GET /users/:id`)
+pub fn rails_action_route(controller: &str, action: &str) -> (&'static str, String) {
+    let resource = to_snake_case(controller.trim_end_matches("Controller"));
+    match action {
+        "index" => ("GET", format!("/{}", resource)),
+        "show" => ("GET", format!("/{}/:id", resource)),
+        "new" => ("GET", format!("/{}/new", resource)),
+        "edit" => ("GET", format!("/{}/:id/edit", resource)),
+        "create" => ("POST", format!("/{}", resource)),
+        "update" => ("PATCH", format!("/{}/:id", resource)),
+        "destroy" => ("DELETE", format!("/{}/:id", resource)),
+        other => ("ANY", format!("/{}/{}", resource, other)),
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_route_paths() {
+        assert_eq!(
+            next_app_route_path(Path::new("web/src/app/(admin)/api/users/[id]/route.ts")),
+            Some("/api/users/[id]".to_string())
+        );
+        assert_eq!(next_app_route_path(Path::new("app/api/page.ts")), None);
+        assert_eq!(
+            next_pages_api_path(Path::new("pages/api/auth/index.ts")),
+            Some("/api/auth".to_string())
+        );
+        assert_eq!(next_pages_api_path(Path::new("pages/about.tsx")), None);
+    }
+
+    #[test]
+    fn test_rails_action_route() {
+        assert_eq!(
+            rails_action_route("LineItemsController", "destroy"),
+            ("DELETE", "/line_items/:id".to_string())
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::Language;
+
+use super::routes::{rails_action_route, with_route};
+use super::{capability_from_span, qualify_by_containment, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind};
+
+/// Ruby extraction (classes, modules, public methods, Rails controller actions
+/// and Minitest/RSpec tests).
+///
+/// Works on the source text. Blocks are delimited by indentation: a
+/// `class`/`module`/`def` ends at the first `end` with the same indentation,
+/// which holds for conventionally formatted code.
+pub struct RubyExtractor;
+
+/// An open `class` or `module` while walking the file
+struct Scope {
+    indent: usize,
+    /// Controller class name, for classes that look like Rails controllers
+    controller: Option<String>,
+    visibility: Visibility,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visibility {
+    Public,
+    Private,
+}
+
+impl LanguageExtractor for RubyExtractor {
+    fn name(&self) -> &'static str {
+        "ruby"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rb"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        None
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let source = file.source;
+        static CONTAINER_REGEX: OnceLock<Regex> = OnceLock::new();
+        static DEF_REGEX: OnceLock<Regex> = OnceLock::new();
+        static ENDLESS_DEF_REGEX: OnceLock<Regex> = OnceLock::new();
+        static TEST_BLOCK_REGEX: OnceLock<Regex> = OnceLock::new();
+        let container_regex = CONTAINER_REGEX.get_or_init(|| {
+            Regex::new(r"^(class|module)\s+([A-Z][\w:]*)(?:\s*<\s*([A-Z][\w:]*))?").expect("valid container regex")
+        });
+        let def_regex = DEF_REGEX.get_or_init(|| {
+            Regex::new(r"^(?:(private|protected|public)\s+)?def\s+(self\.)?([\w?!=]+)").expect("valid def regex")
+        });
+        let endless_def_regex = ENDLESS_DEF_REGEX.get_or_init(|| {
+            Regex::new(r"^(?:\w+\s+)?def\s+[\w.?!]+(?:\([^)]*\))?\s*=[^=~>]").expect("valid endless def regex")
+        });
+        // RSpec `it "..." do` and ActiveSupport `test "..." do`
+        let test_block_regex = TEST_BLOCK_REGEX.get_or_init(|| {
+            Regex::new(r#"^(?:it|specify|test)\s*\(?\s*(?:"([^"]+)"|'([^']+)').*\bdo\b"#).expect("valid test block regex")
+        });
+
+        let lines: Vec<&str> = source.split('\n').collect();
+        let mut offsets = Vec::with_capacity(lines.len());
+        let mut offset = 0;
+        for line in &lines {
+            offsets.push(offset);
+            offset += line.len() + 1;
+        }
+
+        let mut capabilities = Vec::new();
+        let mut scopes: Vec<Scope> = Vec::new();
+
+        for (idx, line) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+
+            // Close scopes ended by this line
+            if trimmed.starts_with("end") && is_keyword_end(trimmed) {
+                if let Some(pos) = scopes.iter().rposition(|s| s.indent == indent) {
+                    scopes.truncate(pos);
+                }
+                continue;
+            }
+
+            if let Some(caps) = container_regex.captures(trimmed) {
+                let name = caps[2].to_string();
+                let superclass = caps.get(3).map(|m| m.as_str()).unwrap_or("");
+                let end_idx = block_end_line(&lines, idx, indent);
+                let kind = if &caps[1] == "module" {
+                    CapabilityKind::Module
+                } else {
+                    CapabilityKind::Class
+                };
+                capabilities.push(capability_from_span(
+                    name.clone(),
+                    kind,
+                    source,
+                    offsets[idx] + indent,
+                    offsets[end_idx] + lines[end_idx].len(),
+                ));
+                scopes.push(Scope {
+                    indent,
+                    controller: (&caps[1] == "class"
+                        && (name.ends_with("Controller") || superclass.ends_with("Controller")))
+                        .then(|| name.rsplit("::").next().unwrap_or(&name).to_string()),
+                    visibility: Visibility::Public,
+                });
+                continue;
+            }
+
+            // Bare visibility keywords switch every following method in the scope
+            if matches!(trimmed.trim_end(), "private" | "protected" | "public") {
+                if let Some(scope) = scopes.iter_mut().rev().find(|s| s.indent < indent) {
+                    scope.visibility = if trimmed.trim_end() == "public" {
+                        Visibility::Public
+                    } else {
+                        Visibility::Private
+                    };
+                }
+                continue;
+            }
+
+            if let Some(caps) = test_block_regex.captures(trimmed) {
+                let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+                let end_idx = block_end_line(&lines, idx, indent);
+                capabilities.push(capability_from_span(
+                    name.to_string(),
+                    CapabilityKind::Test,
+                    source,
+                    offsets[idx] + indent,
+                    offsets[end_idx] + lines[end_idx].len(),
+                ));
+                continue;
+            }
+
+            if let Some(caps) = def_regex.captures(trimmed) {
+                let scope = scopes.iter().rev().find(|s| s.indent < indent);
+                let visibility = match caps.get(1).map(|m| m.as_str()) {
+                    Some("public") => Visibility::Public,
+                    Some(_) => Visibility::Private,
+                    None => scope.map(|s| s.visibility).unwrap_or(Visibility::Public),
+                };
+                if visibility == Visibility::Private {
+                    continue;
+                }
+
+                let is_class_method = caps.get(2).is_some();
+                let controller = scope
+                    .and_then(|s| s.controller.as_deref())
+                    .filter(|_| !is_class_method);
+
+                let end_idx = if is_single_line_def(trimmed, endless_def_regex) {
+                    idx
+                } else {
+                    block_end_line(&lines, idx, indent)
+                };
+                let capability = capability_from_span(
+                    caps[3].to_string(),
+                    CapabilityKind::Function,
+                    source,
+                    offsets[idx] + indent,
+                    offsets[end_idx] + lines[end_idx].len(),
+                );
+                let is_test = !is_class_method && caps[3].starts_with("test_");
+                capabilities.push(match controller {
+                    _ if is_test => Capability {
+                        kind: CapabilityKind::Test,
+                        ..capability
+                    },
+                    // Actions are reachable through the conventional resource routes
+                    Some(controller) => {
+                        let (method, path) = rails_action_route(controller, &caps[3]);
+                        with_route(
+                            Capability {
+                                kind: CapabilityKind::ApiRoute,
+                                ..capability
+                            },
+                            method,
+                            &path,
+                        )
+                    }
+                    None => capability,
+                });
+            }
+        }
+
+        qualify_by_containment(&mut capabilities, "::");
+        Ok(capabilities)
+    }
+}
+
+/// `end`, `end # comment`, `end.freeze` etc., but not identifiers like `ending`
+fn is_keyword_end(trimmed: &str) -> bool {
+    trimmed[3..]
+        .chars()
+        .next()
+        .map(|c| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(true)
+}
+
+/// Endless (`def foo = 1`) or one-line (`def foo; end`) method definitions
+fn is_single_line_def(trimmed: &str, endless_def_regex: &Regex) -> bool {
+    let without_comment = trimmed.split(" #").next().unwrap_or(trimmed).trim_end();
+    without_comment.ends_with(" end")
+        || without_comment.ends_with(";end")
+        || endless_def_regex.is_match(without_comment)
+}
+
+/// Index of the line closing the block opened at `start` with the given indentation
+fn block_end_line(lines: &[&str], start: usize, indent: usize) -> usize {
+    for (idx, line) in lines.iter().enumerate().skip(start + 1) {
+        let trimmed = line.trim_start();
+        if line.len() - trimmed.len() == indent
+            && trimmed.starts_with("end")
+            && is_keyword_end(trimmed)
+        {
+            return idx;
+        }
+    }
+    lines.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_extracts_controller_actions() {
+        let source = r#"module Admin
+  class UsersController < ApplicationController
+    before_action :load_user
+
+    def index
+      @users = User.all
+    end
+
+    def self.permitted_params
+      %i[name email]
+    end
+
+    private
+
+    def load_user
+      @user = User.find(params[:id])
+    end
+  end
+end
+"#;
+        let file = SourceFile {
+            path: Path::new("users_controller.rb"),
+            source,
+            root: None,
+        };
+        let caps = RubyExtractor.extract(&file).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Admin", CapabilityKind::Module),
+                ("UsersController", CapabilityKind::Class),
+                ("index", CapabilityKind::ApiRoute),
+                ("permitted_params", CapabilityKind::Function),
+            ]
+        );
+        assert_eq!(caps[2].line, 5);
+        assert!(caps[2].code_snippet.ends_with("end"));
+        assert_eq!(caps[2].qualified_name, "Admin::UsersController::index");
+        let route = caps[2].route.as_ref().unwrap();
+        assert_eq!((route.method.as_str(), route.path.as_str()), ("GET", "/users"));
+    }
+}
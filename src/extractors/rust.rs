@@ -0,0 +1,183 @@
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Node, Query};
+
+use super::routes::{method_from_name, unquote, with_route};
+use super::{capability_from_node, query, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind, Visibility};
+
+static QUERY: OnceLock<Query> = OnceLock::new();
+
+/// Rust extraction (pub functions, structs, enums, traits, modules, route
+/// handlers, clap commands and tests)
+pub struct RustExtractor;
+
+impl LanguageExtractor for RustExtractor {
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rs"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        Some(tree_sitter_rust::language())
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let mut capabilities = Vec::new();
+        if let Some(root) = file.root {
+            extract_matches(root, file.source, &mut capabilities)?;
+        }
+        Ok(capabilities)
+    }
+}
+
+fn extract_matches(root: Node, source: &str, capabilities: &mut Vec<Capability>) -> Result<()> {
+    let query = query::builtin(&QUERY, tree_sitter_rust::language(), include_str!("queries/rust.scm"));
+
+    for m in query::matches(query, root, source) {
+        if let Some(call) = m.node("route.call") {
+            capabilities.extend(router_routes(&call, source)?);
+            continue;
+        }
+
+        let (kind, node, name) = match (m.definition(), m.name(source)) {
+            (Some((kind, node)), Some(name)) => (kind, node, name.to_string()),
+            _ => continue,
+        };
+        let visibility = match m.text("visibility", source) {
+            Some("pub") => Visibility::Public,
+            Some(_) => Visibility::Internal, // pub(crate), pub(super), pub(in path)
+            None => Visibility::Private,
+        };
+        let attributes = attributes(&node, source)?;
+        let kind = match kind {
+            CapabilityKind::Function if attributes.iter().any(|a| is_test_attribute(a)) => CapabilityKind::Test,
+            // clap: `#[derive(Parser)] struct Cli`, `#[derive(Subcommand)] enum Commands`
+            CapabilityKind::Class | CapabilityKind::Enum
+                if attributes.iter().any(|a| {
+                    a.contains("derive") && (a.contains("Parser") || a.contains("Subcommand"))
+                }) =>
+            {
+                CapabilityKind::CliCommand
+            }
+            kind => kind,
+        };
+
+        if kind == CapabilityKind::Module && visibility == Visibility::Private {
+            continue; // `mod tests`, private helpers
+        } else if kind != CapabilityKind::Function {
+            let mut cap = capability_from_node(name, kind, &node, source);
+            cap.visibility = visibility;
+            capabilities.push(cap);
+        } else if let Some((method, path)) = attribute_route(&attributes)? {
+            // Actix/Rocket handlers are routes whether or not they are pub
+            let mut cap = capability_from_node(name, CapabilityKind::ApiRoute, &node, source);
+            cap.visibility = visibility;
+            capabilities.push(with_route(cap, &method, &path));
+        } else if visibility != Visibility::Private {
+            let mut cap = capability_from_node(name, kind, &node, source);
+            cap.visibility = visibility;
+            capabilities.push(cap);
+        }
+    }
+
+    Ok(())
+}
+
+/// Attributes directly above an item (`#[derive(Parser)]`), nearest first
+fn attributes<'a>(node: &Node, source: &'a str) -> Result<Vec<&'a str>> {
+    let mut attributes = Vec::new();
+    let mut sibling = node.prev_named_sibling();
+    while let Some(attr) = sibling.filter(|s| s.kind() == "attribute_item") {
+        attributes.push(attr.utf8_text(source.as_bytes())?);
+        sibling = attr.prev_named_sibling();
+    }
+    Ok(attributes)
+}
+
+/// `#[test]`, `#[tokio::test]`, `#[rstest]`, `#[test_case(..)]`
+fn is_test_attribute(attribute: &str) -> bool {
+    let path = attribute
+        .trim_start_matches("#[")
+        .split(['(', ']'])
+        .next()
+        .unwrap_or("")
+        .trim();
+    matches!(path.rsplit("::").next(), Some("test" | "rstest" | "test_case"))
+}
+
+/// Actix/Rocket route attributes on a handler: `#[get("/users/{id}")]`,
+/// `#[route("/x", method = "POST")]`
+fn attribute_route(attributes: &[&str]) -> Result<Option<(String, String)>> {
+    static ATTR_REGEX: OnceLock<Regex> = OnceLock::new();
+    static METHOD_REGEX: OnceLock<Regex> = OnceLock::new();
+    let attr_regex = ATTR_REGEX.get_or_init(|| {
+        Regex::new(r#"^#\[\s*(?:\w+::)*(\w+)\s*\(\s*"([^"]*)"(.*)\]$"#).expect("valid route attribute regex")
+    });
+    let method_regex =
+        METHOD_REGEX.get_or_init(|| Regex::new(r#"method\s*=\s*"(\w+)""#).expect("valid route method regex"));
+
+    for text in attributes {
+        if let Some(caps) = attr_regex.captures(text) {
+            let method = if &caps[1] == "route" {
+                method_regex
+                    .captures(&caps[3])
+                    .map(|m| m[1].to_uppercase())
+                    .unwrap_or_else(|| "ANY".to_string())
+            } else {
+                match method_from_name(&caps[1]) {
+                    Some(m) => m.to_string(),
+                    None => continue,
+                }
+            };
+            return Ok(Some((method, caps[2].to_string())));
+        }
+    }
+    Ok(None)
+}
+
+/// Axum/Actix router registrations: `.route("/users", get(list).post(create))`
+/// and `.route("/users", web::get().to(list))`, one capability per method
+fn router_routes(node: &Node, source: &str) -> Result<Vec<Capability>> {
+    let function = match node.child_by_field_name("function") {
+        Some(f) if f.kind() == "field_expression" => f,
+        _ => return Ok(vec![]),
+    };
+    let is_route = function
+        .child_by_field_name("field")
+        .map(|f| f.utf8_text(source.as_bytes()).map(|t| t == "route"))
+        .transpose()?
+        .unwrap_or(false);
+    let args = match node.child_by_field_name("arguments") {
+        Some(a) if is_route => a,
+        _ => return Ok(vec![]),
+    };
+    let path = match args.named_child(0) {
+        Some(p) if p.kind() == "string_literal" => unquote(p.utf8_text(source.as_bytes())?),
+        _ => return Ok(vec![]),
+    };
+    let handlers = match args.named_child(1) {
+        Some(h) => h.utf8_text(source.as_bytes())?,
+        None => return Ok(vec![]),
+    };
+
+    // `get(handler)` (axum) or `get().to(handler)` (actix)
+    static HANDLER_REGEX: OnceLock<Regex> = OnceLock::new();
+    let handler_regex = HANDLER_REGEX.get_or_init(|| {
+        Regex::new(r"\b(get|post|put|delete|patch|head|options|any)\s*\(\s*(?:([\w:]+)\s*\)|\)\s*\.to\s*\(\s*([\w:]+))")
+            .expect("valid route handler regex")
+    });
+    let mut routes = Vec::new();
+    for caps in handler_regex.captures_iter(handlers) {
+        let method = method_from_name(&caps[1]).unwrap_or("ANY");
+        let handler = caps.get(2).or_else(|| caps.get(3)).map(|m| m.as_str()).unwrap_or("");
+        let name = handler.rsplit("::").next().unwrap_or(handler).to_string();
+        let cap = capability_from_node(name, CapabilityKind::ApiRoute, node, source);
+        routes.push(with_route(cap, method, &path));
+    }
+    Ok(routes)
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Parser};
+
+use super::javascript::JavaScriptExtractor;
+use super::typescript::{TsxExtractor, TypeScriptExtractor};
+use super::{capability_from_span, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind};
+
+/// Vue and Svelte single-file components.
+///
+/// The file itself is reported as a `Component` named after the file
+/// (`user-card.vue` -> `UserCard`). Each `<script>` block is parsed with the
+/// grammar its `lang` attribute asks for and extracted like any other
+/// script, with positions mapped back into the component file.
+pub struct SfcExtractor;
+
+impl LanguageExtractor for SfcExtractor {
+    fn name(&self) -> &'static str {
+        "sfc"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["vue", "svelte"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        None // Each script block picks its own
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let source = file.source;
+        static SCRIPT_REGEX: OnceLock<Regex> = OnceLock::new();
+        static LANG_REGEX: OnceLock<Regex> = OnceLock::new();
+        let script_regex = SCRIPT_REGEX
+            .get_or_init(|| Regex::new(r"(?s)<script\b([^>]*)>(.*?)</script>").expect("valid script regex"));
+        let lang_regex =
+            LANG_REGEX.get_or_init(|| Regex::new(r#"\blang\s*=\s*["']?(\w+)"#).expect("valid lang regex"));
+
+        let component = component_name(file);
+        let mut capabilities = vec![capability_from_span(
+            component.clone(),
+            CapabilityKind::Component,
+            source,
+            0,
+            source.trim_end().len(),
+        )];
+
+        let mut parser = Parser::new();
+        for caps in script_regex.captures_iter(source) {
+            let lang = lang_regex.captures(&caps[1]).map(|l| l[1].to_lowercase());
+            let extractor: &dyn LanguageExtractor = match lang.as_deref() {
+                Some("ts") => &TypeScriptExtractor,
+                Some("tsx") => &TsxExtractor,
+                _ => &JavaScriptExtractor,
+            };
+            let script = caps.get(2).unwrap();
+            if let Some(grammar) = extractor.grammar() {
+                parser.set_language(grammar)?;
+            }
+            let tree = parser
+                .parse(script.as_str(), None)
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse script in {}", file.path.display()))?;
+            let block = SourceFile {
+                path: file.path,
+                source: script.as_str(),
+                root: Some(tree.root_node()),
+            };
+
+            let line_offset = source[..script.start()].matches('\n').count();
+            for mut cap in extractor.extract(&block)? {
+                cap.line += line_offset;
+                cap.end_line += line_offset;
+                cap.byte_range = (cap.byte_range.0 + script.start(), cap.byte_range.1 + script.start());
+                cap.qualified_name = format!("{}.{}", component, cap.qualified_name);
+                capabilities.push(cap);
+            }
+        }
+
+        Ok(capabilities)
+    }
+}
+
+/// PascalCase component name from the file name (`user-card.vue` -> `UserCard`)
+fn component_name(file: &SourceFile) -> String {
+    let stem = file.path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    stem.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,139 @@
+use anyhow::Result;
+use regex::Regex;
+use std::sync::OnceLock;
+use tree_sitter::Language;
+
+use super::{brace_block_end, capability_from_span, qualify_by_containment, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind};
+
+/// Solidity extraction (contracts, interfaces, libraries, enums,
+/// public/external functions, Foundry tests, events and modifiers).
+///
+/// Works on the source text: declarations are matched at the start of a line
+/// and their bodies are delimited by brace matching.
+pub struct SolidityExtractor;
+
+impl LanguageExtractor for SolidityExtractor {
+    fn name(&self) -> &'static str {
+        "solidity"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sol"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        None
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let source = file.source;
+        let mut capabilities = Vec::new();
+
+        // Contracts, interfaces, libraries and enums
+        static CONTAINER_REGEX: OnceLock<Regex> = OnceLock::new();
+        let container_regex = CONTAINER_REGEX.get_or_init(|| {
+            Regex::new(r"(?m)^[ \t]*(?:abstract[ \t]+)?(contract|interface|library|enum)[ \t]+([A-Za-z_]\w*)")
+                .expect("valid container regex")
+        });
+        for caps in container_regex.captures_iter(source) {
+            let decl = caps.get(0).unwrap();
+            let start = decl.start() + (decl.as_str().len() - decl.as_str().trim_start().len());
+            let end = brace_block_end(source, decl.end());
+            let kind = match &caps[1] {
+                "interface" => CapabilityKind::Interface,
+                "library" => CapabilityKind::Module,
+                "enum" => CapabilityKind::Enum,
+                _ => CapabilityKind::Class,
+            };
+            capabilities.push(capability_from_span(caps[2].to_string(), kind, source, start, end));
+        }
+
+        // Functions, events and modifiers
+        static MEMBER_REGEX: OnceLock<Regex> = OnceLock::new();
+        let member_regex = MEMBER_REGEX.get_or_init(|| {
+            Regex::new(r"(?m)^[ \t]*(function|event|modifier)[ \t]+([A-Za-z_]\w*)").expect("valid member regex")
+        });
+        for caps in member_regex.captures_iter(source) {
+            let decl = caps.get(0).unwrap();
+            let start = decl.start() + (decl.as_str().len() - decl.as_str().trim_start().len());
+            let end = brace_block_end(source, decl.end());
+
+            if &caps[1] == "function" && !is_externally_visible(&source[decl.end()..end]) {
+                continue;
+            }
+
+            // Foundry runs every public `test*` / `invariant*` function
+            let kind = if &caps[1] == "function" && (caps[2].starts_with("test") || caps[2].starts_with("invariant")) {
+                CapabilityKind::Test
+            } else {
+                CapabilityKind::Function
+            };
+            capabilities.push(capability_from_span(
+                caps[2].to_string(),
+                kind,
+                source,
+                start,
+                end,
+            ));
+        }
+
+        capabilities.sort_by_key(|c| c.line);
+        qualify_by_containment(&mut capabilities, ".");
+        Ok(capabilities)
+    }
+}
+
+/// Whether a function header (up to its body) declares public or external visibility
+fn is_externally_visible(declaration: &str) -> bool {
+    let header = declaration.split('{').next().unwrap_or(declaration);
+    header
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| word == "public" || word == "external")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_extracts_contract_members() {
+        let source = r#"
+pragma solidity ^0.8.0;
+
+interface IVault {
+    function deposit(uint256 amount) external;
+}
+
+contract Vault is IVault {
+    event Deposited(address indexed who, uint256 amount);
+
+    modifier onlyOwner() {
+        require(msg.sender == owner);
+        _;
+    }
+
+    function deposit(uint256 amount) external override {
+        emit Deposited(msg.sender, amount);
+    }
+
+    function _credit(address who) internal {
+        balances[who] += 1;
+    }
+}
+"#;
+        let file = SourceFile {
+            path: Path::new("Vault.sol"),
+            source,
+            root: None,
+        };
+        let caps = SolidityExtractor.extract(&file).unwrap();
+        let names: Vec<_> = caps.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["IVault", "deposit", "Vault", "Deposited", "onlyOwner", "deposit"]
+        );
+        assert!(caps[2].code_snippet.ends_with('}'));
+    }
+}
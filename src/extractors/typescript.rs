@@ -0,0 +1,254 @@
+use anyhow::Result;
+use std::sync::OnceLock;
+use tree_sitter::{Language, Node, Query};
+
+use super::routes::{method_from_name, next_app_route_path, next_pages_api_path, unquote, with_route};
+use super::{capability_from_node, query, starts_uppercase, LanguageExtractor, SourceFile};
+use crate::shredder::{Capability, CapabilityKind, Visibility};
+
+/// Objects whose `.get()`/`.post()` calls are outgoing HTTP requests, not routes
+const HTTP_CLIENTS: &[&str] = &["axios", "http", "https", "fetch", "request", "superagent", "ky", "got", "client", "api", "$http"];
+
+static QUERY: OnceLock<Query> = OnceLock::new();
+static TSX_QUERY: OnceLock<Query> = OnceLock::new();
+
+/// How Next.js maps this file to a URL, if at all
+enum NextRoute {
+    /// `app/**/route.ts`: exported `GET`/`POST`/... handlers
+    App(String),
+    /// `pages/api/**`: the default export handles every method
+    PagesApi(String),
+}
+
+/// TypeScript extraction (functions, classes, interfaces, enums, namespaces,
+/// components, routes, CLI commands and tests)
+pub struct TypeScriptExtractor;
+
+impl LanguageExtractor for TypeScriptExtractor {
+    fn name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ts", "mts", "cts"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        Some(tree_sitter_typescript::language_typescript())
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let query = query::builtin(&QUERY, tree_sitter_typescript::language_typescript(), include_str!("queries/typescript.scm"));
+        extract_script(query, file)
+    }
+}
+
+/// TSX extraction: TypeScript with JSX, parsed with the TSX grammar
+pub struct TsxExtractor;
+
+impl LanguageExtractor for TsxExtractor {
+    fn name(&self) -> &'static str {
+        "tsx"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["tsx"]
+    }
+
+    fn grammar(&self) -> Option<Language> {
+        Some(tree_sitter_typescript::language_tsx())
+    }
+
+    fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+        let query = query::builtin(&TSX_QUERY, tree_sitter_typescript::language_tsx(), include_str!("queries/typescript.scm"));
+        extract_script(query, file)
+    }
+}
+
+/// Run a TypeScript-family query over a parsed file (shared by the
+/// TypeScript, TSX and JavaScript extractors, whose trees share node kinds)
+pub fn extract_script(query: &Query, file: &SourceFile) -> Result<Vec<Capability>> {
+    let next_route = next_app_route_path(file.path)
+        .map(NextRoute::App)
+        .or_else(|| next_pages_api_path(file.path).map(NextRoute::PagesApi));
+
+    let mut capabilities = Vec::new();
+    if let Some(root) = file.root {
+        extract_matches(query, root, file.source, &mut capabilities, next_route.as_ref())?;
+    }
+    Ok(capabilities)
+}
+
+fn extract_matches(
+    query: &Query,
+    root: Node,
+    source: &str,
+    capabilities: &mut Vec<Capability>,
+    next_route: Option<&NextRoute>,
+) -> Result<()> {
+    for m in query::matches(query, root, source) {
+        if let Some(call) = m.node("route.call") {
+            if let Some(route) = express_route(&call, source)? {
+                capabilities.push(route);
+            }
+            continue;
+        }
+
+        let (kind, node) = match m.definition() {
+            Some(definition) => definition,
+            None => continue,
+        };
+        let name = match m.name(source) {
+            // `deploy <env>` is the `deploy` command
+            Some(name) if kind == CapabilityKind::CliCommand => {
+                name.split_whitespace().next().unwrap_or("").to_string()
+            }
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        if kind == CapabilityKind::Function {
+            if let Some(route) = next_route_capability(&name, &node, source, next_route) {
+                capabilities.push(route);
+                continue;
+            }
+        }
+        // Check if it's a React component (PascalCase)
+        let kind = if kind == CapabilityKind::Function && starts_uppercase(&name) {
+            CapabilityKind::Component
+        } else {
+            kind
+        };
+        let mut cap = capability_from_node(name, kind, &node, source);
+        // Declarations are private unless exported; class methods, tests and
+        // commands are reachable regardless
+        let is_declaration = !matches!(node.kind(), "method_definition" | "call_expression");
+        if is_declaration && exporting_statement(&node).is_none() {
+            cap.visibility = Visibility::Private;
+        }
+        capabilities.push(cap);
+    }
+
+    Ok(())
+}
+
+/// Next.js handlers: exported `GET`/`POST`/... in App Router `route.ts` files,
+/// or the default export of a Pages Router API file
+fn next_route_capability(
+    name: &str,
+    node: &Node,
+    source: &str,
+    next_route: Option<&NextRoute>,
+) -> Option<Capability> {
+    let export = exporting_statement(node)?;
+    match next_route? {
+        NextRoute::App(path) => {
+            let method = method_from_name(name).filter(|_| name.chars().all(|c| c.is_ascii_uppercase()))?;
+            let cap = capability_from_node(name.to_string(), CapabilityKind::ApiRoute, &export, source);
+            Some(with_route(cap, method, path))
+        }
+        NextRoute::PagesApi(path) => {
+            let is_default = (0..export.child_count())
+                .filter_map(|i| export.child(i))
+                .any(|c| c.kind() == "default");
+            if !is_default {
+                return None;
+            }
+            let cap = capability_from_node(name.to_string(), CapabilityKind::ApiRoute, &export, source);
+            Some(with_route(cap, "ANY", path))
+        }
+    }
+}
+
+/// The `export` statement directly wrapping a declaration, if any
+fn exporting_statement<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let mut current = node.parent()?;
+    if current.kind() == "lexical_declaration" {
+        current = current.parent()?;
+    }
+    (current.kind() == "export_statement").then_some(current)
+}
+
+/// Express/Fastify/Koa-style registrations: `app.get('/users/:id', handler)`
+/// and `fastify.route({ method: 'GET', url: '/users', handler })`
+fn express_route(node: &Node, source: &str) -> Result<Option<Capability>> {
+    let function = match node.child_by_field_name("function") {
+        Some(f) if f.kind() == "member_expression" => f,
+        _ => return Ok(None),
+    };
+    let (object, property) = match (
+        function.child_by_field_name("object"),
+        function.child_by_field_name("property"),
+    ) {
+        (Some(o), Some(p)) => (o, p),
+        _ => return Ok(None),
+    };
+    let object_name = object.utf8_text(source.as_bytes())?;
+    let object_name = object_name.rsplit('.').next().unwrap_or(object_name);
+    if HTTP_CLIENTS.contains(&object_name) {
+        return Ok(None);
+    }
+
+    let args: Vec<Node> = match node.child_by_field_name("arguments") {
+        Some(a) => (0..a.named_child_count()).filter_map(|i| a.named_child(i)).collect(),
+        None => return Ok(None),
+    };
+    let property = property.utf8_text(source.as_bytes())?;
+
+    let (method, path) = if property == "route" {
+        // Fastify full declaration
+        let options = match args.first() {
+            Some(o) if o.kind() == "object" => *o,
+            _ => return Ok(None),
+        };
+        let mut method = None;
+        let mut url = None;
+        for i in 0..options.named_child_count() {
+            let pair = options.named_child(i).unwrap();
+            if pair.kind() != "pair" {
+                continue;
+            }
+            let (key, value) = match (pair.child_by_field_name("key"), pair.child_by_field_name("value")) {
+                (Some(k), Some(v)) => (k, v),
+                _ => continue,
+            };
+            match unquote(key.utf8_text(source.as_bytes())?).as_str() {
+                "method" => method = Some(unquote(value.utf8_text(source.as_bytes())?).to_uppercase()),
+                "url" | "path" => url = Some(unquote(value.utf8_text(source.as_bytes())?)),
+                _ => {}
+            }
+        }
+        match (method, url) {
+            (Some(m), Some(u)) => (m, u),
+            _ => return Ok(None),
+        }
+    } else {
+        let method = match method_from_name(property) {
+            Some(m) if property != "request" => m,
+            _ => return Ok(None),
+        };
+        // A route needs a path and at least one handler after it
+        let path = match args.first() {
+            Some(p) if matches!(p.kind(), "string" | "template_string") => {
+                unquote(p.utf8_text(source.as_bytes())?)
+            }
+            _ => return Ok(None),
+        };
+        let has_handler = args.len() >= 2
+            && matches!(
+                args.last().unwrap().kind(),
+                "arrow_function" | "function" | "function_expression" | "identifier" | "member_expression"
+            );
+        if !path.starts_with('/') || !has_handler {
+            return Ok(None);
+        }
+        (method.to_string(), path)
+    };
+
+    let name = format!("{} {}", method, path);
+    let cap = capability_from_node(name, CapabilityKind::ApiRoute, node, source);
+    Ok(Some(with_route(cap, &method, &path)))
+}
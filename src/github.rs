@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::fs;
 
-use crate::shredder::{Shredder, CapabilityKind};
+use crate::shredder::Shredder;
 use crate::vectorizer::{Vectorizer, EmbeddedCapability};
 
 /// THE GITHUB INTEGRATOR: Scans GitHub repositories for capabilities
@@ -172,6 +172,16 @@ impl GitHubIntegrator {
                             || path.ends_with(".py")
                             || path.ends_with(".js")
                             || path.ends_with(".jsx")
+                            || path.ends_with(".mjs")
+                            || path.ends_with(".cjs")
+                            || path.ends_with(".vue")
+                            || path.ends_with(".svelte")
+                            || path.ends_with(".ipynb")
+                            || path.ends_with(".sol")
+                            || path.ends_with(".rb")
+                            || path.ends_with(".java")
+                            || path.ends_with(".kt")
+                            || path.ends_with(".cs")
                     } else {
                         false
                     }
@@ -216,7 +226,7 @@ impl GitHubIntegrator {
                                 for cap in capabilities {
                                     all_capabilities.push((
                                         temp_path.clone(),
-                                        path.split('.').last().unwrap_or("").to_string(),
+                                        path.split('.').next_back().unwrap_or("").to_string(),
                                         cap,
                                     ));
                                 }
@@ -233,6 +243,7 @@ impl GitHubIntegrator {
             }
         }
 
+        let all_capabilities = crate::evidence::attach_tests(all_capabilities);
         println!(
             "  {} Extracted {} capabilities",
             "[+]".green(),
@@ -240,28 +251,13 @@ impl GitHubIntegrator {
         );
 
         // 5. Generate embeddings if vectorizer is available
+        let repo_path = format!("github.com/{}/{}", owner, repo);
         if let Some(v) = &self.vectorizer {
             println!("  {} Generating embeddings...", "[+]".green());
             
             let embedding_tasks: Vec<_> = all_capabilities
                 .iter()
-                .map(|(_path, ext, cap)| {
-                    let kind_str = match cap.kind {
-                        CapabilityKind::Function => "function",
-                        CapabilityKind::Class => "class",
-                        CapabilityKind::Component => "component",
-                        CapabilityKind::ApiRoute => "api_route",
-                    };
-                    (
-                        cap.name.clone(),
-                        cap.code_snippet.clone(),
-                        ext.clone(),
-                        kind_str.to_string(),
-                        format!("github.com/{}/{}", owner, repo),
-                        cap.line,
-                        cap.authorship.clone(),
-                    )
-                })
+                .map(|(_path, ext, cap)| EmbeddedCapability::from_capability(cap, ext, &repo_path))
                 .collect();
 
             match v.embed_capabilities(embedding_tasks).await {
@@ -278,42 +274,7 @@ impl GitHubIntegrator {
                     // Return capabilities without embeddings
                     Ok(all_capabilities
                         .into_iter()
-                        .map(|(_, _, cap)| {
-                            let (author_email, author_name, commit_sha, authorship_confidence, is_self_authored, contribution_percentage) =
-                                if let Some(auth) = &cap.authorship {
-                                    (
-                                        auth.author_email.clone(),
-                                        auth.author_name.clone(),
-                                        auth.commit_sha.clone(),
-                                        Some(auth.authorship_confidence),
-                                        Some(auth.is_self_authored),
-                                        Some(auth.contribution_percentage),
-                                    )
-                                } else {
-                                    (None, None, None, None, None, None)
-                                };
-
-                            EmbeddedCapability {
-                                name: cap.name,
-                                code_snippet: cap.code_snippet,
-                                embedding: vec![],
-                                language: "unknown".to_string(),
-                                kind: match cap.kind {
-                                    CapabilityKind::Function => "function".to_string(),
-                                    CapabilityKind::Class => "class".to_string(),
-                                    CapabilityKind::Component => "component".to_string(),
-                                    CapabilityKind::ApiRoute => "api_route".to_string(),
-                                },
-                                path: format!("github.com/{}/{}", owner, repo),
-                                line: cap.line,
-                                author_email,
-                                author_name,
-                                commit_sha,
-                                authorship_confidence,
-                                is_self_authored,
-                                contribution_percentage,
-                            }
-                        })
+                        .map(|(_, ext, cap)| EmbeddedCapability::from_capability(&cap, &ext, &repo_path))
                         .collect())
                 }
             }
@@ -321,42 +282,7 @@ impl GitHubIntegrator {
             // Return capabilities without embeddings
             Ok(all_capabilities
                 .into_iter()
-                .map(|(_, _, cap)| {
-                    let (author_email, author_name, commit_sha, authorship_confidence, is_self_authored, contribution_percentage) =
-                        if let Some(auth) = &cap.authorship {
-                            (
-                                auth.author_email.clone(),
-                                auth.author_name.clone(),
-                                auth.commit_sha.clone(),
-                                Some(auth.authorship_confidence),
-                                Some(auth.is_self_authored),
-                                Some(auth.contribution_percentage),
-                            )
-                        } else {
-                            (None, None, None, None, None, None)
-                        };
-
-                    EmbeddedCapability {
-                        name: cap.name,
-                        code_snippet: cap.code_snippet,
-                        embedding: vec![],
-                        language: "unknown".to_string(),
-                        kind: match cap.kind {
-                            CapabilityKind::Function => "function".to_string(),
-                            CapabilityKind::Class => "class".to_string(),
-                            CapabilityKind::Component => "component".to_string(),
-                            CapabilityKind::ApiRoute => "api_route".to_string(),
-                        },
-                        path: format!("github.com/{}/{}", owner, repo),
-                        line: cap.line,
-                        author_email,
-                        author_name,
-                        commit_sha,
-                        authorship_confidence,
-                        is_self_authored,
-                        contribution_percentage,
-                    }
-                })
+                .map(|(_, ext, cap)| EmbeddedCapability::from_capability(&cap, &ext, &repo_path))
                 .collect())
         }
     }
@@ -0,0 +1,611 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::shredder::Capability;
+use crate::vectorizer::EmbeddedCapability;
+
+/// Extensions whose imports use JS module specifiers
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs", "vue", "svelte"];
+
+/// Identifiers followed by `(` that are control flow, not calls
+const NOT_CALLS: &[&str] = &[
+    "if", "for", "while", "switch", "match", "return", "catch", "with", "elif", "except", "and",
+    "or", "not", "in", "fn", "def", "func", "function", "sizeof", "typeof", "await", "yield",
+    "loop", "assert", "print", "super", "this", "self", "Some", "Ok", "Err",
+];
+
+/// THE GRAPH: Intra-repo dependency graph between capabilities and files
+///
+/// Capabilities `call` the capabilities named in their bodies, files
+/// `import` other scanned files and `define` their capabilities. A
+/// capability's weight is the number of lines in its dependency closure
+/// (itself plus everything it transitively calls).
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CapabilityGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub kind: String, // "file" or a capability kind
+    pub name: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub lines: usize,  // Lines spanned by the node itself
+    pub weight: usize, // Lines in the node's dependency closure
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    Calls,
+    Imports,
+    Defines,
+}
+
+/// Graph node id of a capability: `path#qualified_name:line`, or
+/// `path#qualified_name:cell:line` in a notebook, whose lines are per cell
+pub fn capability_id(path: &str, qualified_name: &str, cell: Option<usize>, line: usize) -> String {
+    match cell {
+        Some(cell) => format!("{}#{}:{}:{}", path, qualified_name, cell, line),
+        None => format!("{}#{}:{}", path, qualified_name, line),
+    }
+}
+
+/// Names a capability body calls: `parse(`, `self.store.get(`, `Config::load(`
+pub fn called_names(body: &str, own_name: &str) -> Vec<String> {
+    static CALL_REGEX: OnceLock<Regex> = OnceLock::new();
+    let call_regex = CALL_REGEX
+        .get_or_init(|| Regex::new(r"([A-Za-z_]\w*)\s*(?:::<[^>]*>\s*)?!?\(").expect("valid call regex"));
+    let mut seen = HashSet::new();
+    call_regex
+        .captures_iter(body)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| name != own_name && !NOT_CALLS.contains(&name.as_str()))
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+/// Raw import specifiers of a source file, by language:
+/// `./parser`, `app.models`, `crate::graph`, `github.com/x/y/pkg`
+pub fn imports(path: &Path, source: &str) -> Vec<String> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let Some(regex) = import_regex(ext) else {
+        return Vec::new();
+    };
+    regex
+        .captures_iter(source)
+        .filter_map(|caps| caps.iter().skip(1).flatten().next().map(|m| m.as_str().to_string()))
+        // Bare JS specifiers are npm packages
+        .filter(|spec| !SCRIPT_EXTENSIONS.contains(&ext) || spec.starts_with('.'))
+        .map(|spec| match ext {
+            // `require_relative 'x'` is relative to the requiring file
+            "rb" if source.contains(&format!("require_relative '{}'", spec))
+                || source.contains(&format!("require_relative \"{}\"", spec)) =>
+            {
+                format!("./{}", spec)
+            }
+            "rs" if !spec.contains("::") => format!("self::{}", spec), // `mod x;`
+            _ => spec,
+        })
+        .collect()
+}
+
+/// Import statement regex for a file extension
+fn import_regex(ext: &str) -> Option<&'static Regex> {
+    static SCRIPT_REGEX: OnceLock<Regex> = OnceLock::new();
+    static PYTHON_REGEX: OnceLock<Regex> = OnceLock::new();
+    static RUST_REGEX: OnceLock<Regex> = OnceLock::new();
+    static GO_REGEX: OnceLock<Regex> = OnceLock::new();
+    static JVM_REGEX: OnceLock<Regex> = OnceLock::new();
+    static RUBY_REGEX: OnceLock<Regex> = OnceLock::new();
+    static SOLIDITY_REGEX: OnceLock<Regex> = OnceLock::new();
+    let (regex, pattern) = match ext {
+        _ if SCRIPT_EXTENSIONS.contains(&ext) => (
+            &SCRIPT_REGEX,
+            r#"(?m)(?:\bfrom\s+|^\s*import\s+|\brequire\s*\(\s*|\bimport\s*\(\s*)["']([^"']+)["']"#,
+        ),
+        "py" => (&PYTHON_REGEX, r"(?m)^\s*(?:from\s+(\.*[\w.]*)\s+import|import\s+([\w.]+))"),
+        "rs" => (
+            &RUST_REGEX,
+            r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?(?:use\s+((?:crate|super|self)(?:::\w+)+)|mod\s+(\w+)\s*;)",
+        ),
+        "go" => (&GO_REGEX, r#"(?m)^\s*(?:import\s+)?(?:\w+\s+)?"([\w./-]+)"\s*$"#),
+        "java" | "kt" => (&JVM_REGEX, r"(?m)^\s*import\s+(?:static\s+)?([\w.]+)"),
+        "rb" => (&RUBY_REGEX, r#"(?m)^\s*require(?:_relative)?\s*\(?\s*["']([^"']+)["']"#),
+        "sol" => (&SOLIDITY_REGEX, r#"(?m)^\s*import\s+(?:[^"']*\bfrom\s+)?["']([^"']+)["']"#),
+        _ => return None,
+    };
+    Some(regex.get_or_init(|| Regex::new(pattern).expect("valid import regex")))
+}
+
+impl CapabilityGraph {
+    /// Build the graph for scanned capabilities, reading each file once for
+    /// its imports
+    pub fn build(capabilities: &[(PathBuf, String, Capability)]) -> Self {
+        let mut files: Vec<&PathBuf> = capabilities.iter().map(|(path, _, _)| path).collect();
+        files.sort();
+        files.dedup();
+
+        let modules = ModuleIndex::new(&files);
+        let mut graph = Self::default();
+        let mut file_lines = HashMap::new();
+        let mut file_imports: HashMap<&Path, HashSet<&Path>> = HashMap::new();
+        for path in &files {
+            let source = fs::read_to_string(path).unwrap_or_default();
+            file_lines.insert(path.as_path(), source.lines().count());
+            let targets: HashSet<&Path> = imports(path, &source)
+                .iter()
+                .flat_map(|spec| resolve_import(path, spec, &modules))
+                .filter(|target| target != path)
+                .collect();
+            for target in &targets {
+                graph.edges.push(GraphEdge {
+                    from: path.display().to_string(),
+                    to: target.display().to_string(),
+                    kind: EdgeKind::Imports,
+                });
+            }
+            file_imports.insert(path.as_path(), targets);
+        }
+
+        let ids: Vec<String> = capabilities
+            .iter()
+            .map(|(path, _, cap)| capability_id(&path.display().to_string(), &cap.qualified_name, cap.cell, cap.line))
+            .collect();
+        let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, (_, _, cap)) in capabilities.iter().enumerate() {
+            by_name.entry(cap.name.as_str()).or_default().push(i);
+        }
+
+        for (i, (path, _, cap)) in capabilities.iter().enumerate() {
+            graph.edges.push(GraphEdge {
+                from: path.display().to_string(),
+                to: ids[i].clone(),
+                kind: EdgeKind::Defines,
+            });
+
+            let imported = file_imports.get(path.as_path());
+            for name in &cap.calls {
+                let candidates: Vec<usize> = by_name
+                    .get(name.as_str())
+                    .map(|c| c.iter().copied().filter(|&j| j != i).collect())
+                    .unwrap_or_default();
+                // Same file first, then imported files, then unique names
+                let in_file = |j: &usize| capabilities[*j].0 == *path;
+                let in_imports = |j: &usize| imported.map(|f| f.contains(capabilities[*j].0.as_path())).unwrap_or(false);
+                let callees: Vec<usize> = if candidates.iter().any(in_file) {
+                    candidates.into_iter().filter(in_file).collect()
+                } else if candidates.iter().any(in_imports) {
+                    candidates.into_iter().filter(in_imports).collect()
+                } else if candidates.len() == 1 {
+                    candidates
+                } else {
+                    Vec::new()
+                };
+                for j in callees {
+                    graph.edges.push(GraphEdge {
+                        from: ids[i].clone(),
+                        to: ids[j].clone(),
+                        kind: EdgeKind::Calls,
+                    });
+                }
+            }
+        }
+
+        for path in &files {
+            let lines = file_lines.get(path.as_path()).copied().unwrap_or(0);
+            graph.nodes.push(GraphNode {
+                id: path.display().to_string(),
+                kind: "file".to_string(),
+                name: path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string(),
+                path: path.display().to_string(),
+                line: None,
+                lines,
+                weight: lines,
+            });
+        }
+        for (i, (path, _, cap)) in capabilities.iter().enumerate() {
+            let lines = cap.end_line.saturating_sub(cap.line) + 1;
+            graph.nodes.push(GraphNode {
+                id: ids[i].clone(),
+                kind: cap.kind.as_str().to_string(),
+                name: cap.name.clone(),
+                path: path.display().to_string(),
+                line: Some(cap.line),
+                lines,
+                weight: lines,
+            });
+        }
+
+        let weights = graph.closure_weights();
+        for (node, weight) in graph.nodes.iter_mut().zip(weights) {
+            node.weight = weight;
+        }
+        graph
+    }
+
+    /// Lines in each node's dependency closure, in node order. Closures are
+    /// built once per strongly connected component (nodes in a cycle share
+    /// one), from those of the components it depends on.
+    fn closure_weights(&self) -> Vec<usize> {
+        let index: HashMap<&str, usize> = self.nodes.iter().enumerate().map(|(i, n)| (n.id.as_str(), i)).collect();
+        let dependencies = self.dependencies();
+        let successors: Vec<Vec<usize>> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                dependencies
+                    .get(node.id.as_str())
+                    .map(|next| next.iter().filter_map(|id| index.get(id).copied()).collect())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let components = strongly_connected(&successors);
+        let mut component_of = vec![0; self.nodes.len()];
+        for (c, members) in components.iter().enumerate() {
+            for &member in members {
+                component_of[member] = c;
+            }
+        }
+        let mut closures: Vec<Vec<usize>> = Vec::with_capacity(components.len());
+        let mut weights = vec![0; self.nodes.len()];
+        for (c, members) in components.iter().enumerate() {
+            let mut closure = BTreeSet::from([c]);
+            for &member in members {
+                for &next in &successors[member] {
+                    // Already there means already there with its own closure
+                    let d = component_of[next];
+                    if closure.insert(d) {
+                        closure.extend(closures[d].iter().copied());
+                    }
+                }
+            }
+            let weight = closure
+                .iter()
+                .flat_map(|&d| &components[d])
+                .map(|&i| self.nodes[i].lines)
+                .sum();
+            for &member in members {
+                weights[member] = weight;
+            }
+            closures.push(closure.into_iter().collect());
+        }
+        weights
+    }
+
+    /// Outgoing `calls` and `imports` edges by source node
+    fn dependencies(&self) -> HashMap<&str, Vec<&str>> {
+        let mut dependencies: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in self.edges.iter().filter(|e| e.kind != EdgeKind::Defines) {
+            dependencies.entry(edge.from.as_str()).or_default().push(edge.to.as_str());
+        }
+        dependencies
+    }
+
+    /// Copy resolved callees and closure weight onto embedded capabilities
+    pub fn annotate(&self, capabilities: &mut [EmbeddedCapability]) {
+        let nodes: HashMap<&str, &GraphNode> = self.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let mut calls: HashMap<&str, Vec<String>> = HashMap::new();
+        for edge in self.edges.iter().filter(|e| e.kind == EdgeKind::Calls) {
+            calls.entry(edge.from.as_str()).or_default().push(edge.to.clone());
+        }
+        for cap in capabilities {
+            let id = capability_id(&cap.path, cap.qualified_name.as_deref().unwrap_or(&cap.name), cap.cell, cap.line);
+            if let Some(node) = nodes.get(id.as_str()) {
+                cap.weight = Some(node.weight);
+                cap.calls = calls.get(id.as_str()).cloned().unwrap_or_default();
+            }
+        }
+    }
+}
+
+/// Strongly connected components of a graph given as successor lists, each
+/// after every component it reaches (Tarjan's algorithm, without recursion)
+fn strongly_connected(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let mut index = vec![UNVISITED; successors.len()];
+    let mut low = vec![0; successors.len()];
+    let mut on_stack = vec![false; successors.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut counter = 0;
+    for root in 0..successors.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        let mut work: Vec<(usize, usize)> = Vec::new(); // (node, next successor)
+        let mut next = Some(root);
+        loop {
+            if let Some(node) = next.take() {
+                index[node] = counter;
+                low[node] = counter;
+                counter += 1;
+                stack.push(node);
+                on_stack[node] = true;
+                work.push((node, 0));
+            }
+            let Some((node, position)) = work.last_mut() else { break };
+            let node = *node;
+            if let Some(&child) = successors[node].get(*position) {
+                *position += 1;
+                if index[child] == UNVISITED {
+                    next = Some(child);
+                } else if on_stack[child] {
+                    low[node] = low[node].min(index[child]);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Scanned files an import specifier refers to
+fn resolve_import<'a>(from: &Path, spec: &str, modules: &ModuleIndex<'a>) -> Vec<&'a Path> {
+    let dir = from.parent().unwrap_or(Path::new(""));
+    let (base, relative) = if spec.starts_with("./") || spec.starts_with("../") {
+        (normalize(&dir.join(spec)), true)
+    } else if let Some(rest) = spec.strip_prefix("self::") {
+        (normalize(&rust_module_dir(from).join(rest.replace("::", "/"))), true)
+    } else if let Some(rest) = spec.strip_prefix("super::") {
+        (normalize(&rust_module_dir(from).join("..").join(rest.replace("::", "/"))), true)
+    } else if let Some(rest) = spec.strip_prefix("crate::") {
+        (PathBuf::from(rest.replace("::", "/")), false)
+    } else if spec.starts_with('.') {
+        // Python relative import: `.models`, `..core.db`
+        let dots = spec.chars().take_while(|c| *c == '.').count();
+        let mut base = dir.to_path_buf();
+        for _ in 1..dots {
+            base.push("..");
+        }
+        (normalize(&base.join(spec[dots..].replace('.', "/"))), true)
+    } else if spec.contains('/') {
+        (PathBuf::from(spec), false) // Go package path, bare JS/Ruby path
+    } else {
+        (PathBuf::from(spec.replace('.', "/")), false) // Dotted module/class path
+    };
+
+    let mut bases = vec![base.clone()];
+    if spec.contains("::") {
+        // Rust `use crate::a::b::Item` may name an item inside `a/b.rs`
+        let mut parent = base.parent();
+        while let Some(p) = parent.filter(|p| !p.as_os_str().is_empty()) {
+            bases.push(p.to_path_buf());
+            parent = p.parent();
+        }
+    } else if from.extension().and_then(|e| e.to_str()) == Some("go") && base.components().count() >= 3 {
+        // `github.com/acme/shop/internal/cart`: the module prefix isn't on disk
+        let components: Vec<_> = base.components().collect();
+        for skip in 1..components.len() {
+            bases.push(components[skip..].iter().collect());
+        }
+    }
+
+    bases
+        .iter()
+        .map(|base| modules.lookup(base, relative))
+        .find(|matched| !matched.is_empty())
+        .unwrap_or_default()
+}
+
+/// Scanned files by the module paths they can be imported as: `base` for
+/// `base.ext`, `base/index.ext`, `base/__init__.py`, `base/mod.rs`, or (for
+/// Go packages) any file in `base/`
+struct ModuleIndex<'a> {
+    by_path: HashMap<PathBuf, Vec<&'a Path>>,              // Normalized module path
+    by_name: HashMap<OsString, Vec<(PathBuf, &'a Path)>>, // Module path's last component
+}
+
+impl<'a> ModuleIndex<'a> {
+    fn new(files: &[&'a PathBuf]) -> Self {
+        let mut index = Self {
+            by_path: HashMap::new(),
+            by_name: HashMap::new(),
+        };
+        for file in files {
+            let without_ext = file.with_extension("");
+            let is_index = matches!(
+                without_ext.file_name().and_then(|n| n.to_str()),
+                Some("index" | "__init__" | "mod" | "lib")
+            );
+            let is_go = file.extension().and_then(|e| e.to_str()) == Some("go");
+            let mut modules = vec![without_ext.clone()];
+            if is_index || is_go {
+                modules.extend(without_ext.parent().map(Path::to_path_buf));
+            }
+            for module in modules {
+                index.by_path.entry(normalize(&module)).or_default().push(file);
+                if let Some(name) = module.file_name() {
+                    index.by_name.entry(name.to_os_string()).or_default().push((module.clone(), file));
+                }
+            }
+        }
+        index
+    }
+
+    /// Files that are the module `base` names: by path if `relative` (where
+    /// `./parser.js` in a TypeScript file names `parser.ts`), else any whose
+    /// module path ends with `base`. In scan order.
+    fn lookup(&self, base: &Path, relative: bool) -> Vec<&'a Path> {
+        let mut matched: Vec<&'a Path> = if relative {
+            [base.to_path_buf(), base.with_extension("")]
+                .iter()
+                .flat_map(|path| self.by_path.get(path))
+                .flatten()
+                .copied()
+                .collect()
+        } else {
+            base.file_name()
+                .and_then(|name| self.by_name.get(name))
+                .into_iter()
+                .flatten()
+                .filter(|(module, _)| module.ends_with(base))
+                .map(|(_, file)| *file)
+                .collect()
+        };
+        matched.sort();
+        matched.dedup();
+        matched
+    }
+}
+
+/// Directory holding a Rust file's child modules (`src/a.rs` -> `src/a`,
+/// `src/a/mod.rs` and `src/main.rs` -> their own directory)
+fn rust_module_dir(file: &Path) -> PathBuf {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    match file.file_stem().and_then(|s| s.to_str()) {
+        Some("mod" | "main" | "lib") | None => dir.to_path_buf(),
+        Some(stem) => dir.join(stem),
+    }
+}
+
+/// Resolve `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shredder::Shredder;
+
+    impl CapabilityGraph {
+        fn node(&self, id: &str) -> Option<&GraphNode> {
+            self.nodes.iter().find(|n| n.id == id)
+        }
+
+        /// Ids of a node and everything it calls or imports, transitively
+        fn closure(&self, id: &str) -> Vec<String> {
+            let dependencies = self.dependencies();
+            let mut seen: HashSet<&str> = HashSet::new();
+            let mut stack = vec![id];
+            while let Some(current) = stack.pop() {
+                if seen.insert(current) {
+                    stack.extend(dependencies.get(current).into_iter().flatten().copied());
+                }
+            }
+            seen.into_iter().map(str::to_string).collect()
+        }
+    }
+
+    #[test]
+    fn test_builds_call_and_import_edges() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("src/api.ts", "import { loadUser } from './users';\n\nexport function getUser(id: string) {\n  return loadUser(id);\n}\n"),
+            ("src/users.ts", "import { query } from './db';\n\nexport function loadUser(id: string) {\n  const row = query(id);\n  return row;\n}\n"),
+            ("src/db.ts", "export function query(sql: string) {\n  return null;\n}\n"),
+        ];
+        let mut shredder = Shredder::new().unwrap();
+        let mut caps = Vec::new();
+        for (name, source) in files {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, source).unwrap();
+            for cap in shredder.shred_file(&path).unwrap() {
+                caps.push((path.clone(), "ts".to_string(), cap));
+            }
+        }
+
+        let graph = CapabilityGraph::build(&caps);
+        let id = |file: &str, name: &str, line: usize| {
+            capability_id(&dir.path().join(file).display().to_string(), name, None, line)
+        };
+        let get_user = id("src/api.ts", "getUser", 3);
+        assert!(graph.edges.contains(&GraphEdge {
+            from: get_user.clone(),
+            to: id("src/users.ts", "loadUser", 3),
+            kind: EdgeKind::Calls,
+        }));
+        assert!(graph.edges.contains(&GraphEdge {
+            from: dir.path().join("src/users.ts").display().to_string(),
+            to: dir.path().join("src/db.ts").display().to_string(),
+            kind: EdgeKind::Imports,
+        }));
+        // getUser (3 lines) + loadUser (4) + query (3)
+        assert_eq!(graph.closure(&get_user).len(), 3);
+        assert_eq!(graph.node(&get_user).unwrap().weight, 10);
+    }
+
+    #[test]
+    fn test_weighs_cycles_once() {
+        let source = "function ping(n: number) {\n  return pong(n - 1);\n}\n\nfunction pong(n: number) {\n  return ping(n - 1);\n}\n\nfunction serve() {\n  ping(3);\n}\n";
+        let path = PathBuf::from("missing/rally.ts");
+        let mut shredder = Shredder::new().unwrap();
+        let caps: Vec<_> = shredder
+            .shred_source(&path, source)
+            .unwrap()
+            .into_iter()
+            .map(|cap| (path.clone(), "ts".to_string(), cap))
+            .collect();
+
+        let graph = CapabilityGraph::build(&caps);
+        let weight = |name: &str, line: usize| graph.node(&capability_id("missing/rally.ts", name, None, line)).unwrap().weight;
+        // ping and pong (3 lines each) reach each other; serve (3) reaches both
+        assert_eq!((weight("ping", 1), weight("pong", 5), weight("serve", 9)), (6, 6, 9));
+    }
+
+    #[test]
+    fn test_keeps_notebook_cells_apart() {
+        // Each cell redefines `load` on its first line
+        let source = r#"{"cells": [
+  {"cell_type": "code", "source": ["def load(path):\n", "    return path\n"]},
+  {"cell_type": "code", "source": ["def load(path):\n", "    return open(path)\n"]}
+]}"#;
+        let path = PathBuf::from("missing/explore.ipynb");
+        let mut shredder = Shredder::new().unwrap();
+        let caps: Vec<_> = shredder
+            .shred_source(&path, source)
+            .unwrap()
+            .into_iter()
+            .map(|cap| (path.clone(), "ipynb".to_string(), cap))
+            .collect();
+
+        let graph = CapabilityGraph::build(&caps);
+        assert!(graph.node(&capability_id("missing/explore.ipynb", "load", Some(0), 1)).is_some());
+        assert!(graph.node(&capability_id("missing/explore.ipynb", "load", Some(1), 1)).is_some());
+    }
+}
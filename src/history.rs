@@ -0,0 +1,163 @@
+use anyhow::Result;
+use git2::{ObjectType, Oid, Repository, Tree, TreeWalkMode, TreeWalkResult};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::analyzer::Analyzer;
+use crate::evidence;
+use crate::shredder::Shredder;
+use crate::vectorizer::EmbeddedCapability;
+
+/// Capabilities of a repository at one commit
+#[derive(Debug, Serialize)]
+pub struct RevisionScan {
+    pub revision: String, // As requested ("v1.0", "HEAD~10", a SHA)
+    pub commit: String,
+    pub committed_at: String, // RFC 3339
+    pub files_scanned: usize,
+    pub kinds: BTreeMap<String, usize>, // Capability count per kind, for timelines
+    pub capabilities: Vec<EmbeddedCapability>,
+}
+
+/// THE HISTORY: Scan the tree of any commit or tag, reading blobs straight
+/// from the object database, so nothing is checked out.
+///
+/// `scannable` picks the files to shred, as for a scan of the working tree.
+/// Paths are relative to the repository root. Capabilities are not blamed.
+pub fn scan_revision(
+    repo: &Repository,
+    revision: &str,
+    shredder: &mut Shredder,
+    scannable: fn(&Path) -> bool,
+) -> Result<RevisionScan> {
+    let commit = repo.revparse_single(revision)?.peel_to_commit()?;
+    let tree = commit.tree()?;
+
+    let mut blobs: Vec<(PathBuf, Oid)> = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            let path = Path::new(dir).join(entry.name().unwrap_or(""));
+            if scannable(&path) {
+                blobs.push((path, entry.id()));
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let read_blob = |id: Oid| -> Option<String> {
+        let blob = repo.find_blob(id).ok()?;
+        String::from_utf8(blob.content().to_vec()).ok()
+    };
+
+    let mut scanned = Vec::new();
+    for (path, id) in &blobs {
+        let source = match read_blob(*id) {
+            Some(source) => source,
+            None => continue, // Binary or not UTF-8
+        };
+        let analyzer = Analyzer::for_path(path);
+        let read_lockfile = |lockfile: &str| read_tree_file(repo, &tree, &sibling(path, lockfile));
+        let capabilities = analyzer.analyze(shredder, path, &source, &read_lockfile);
+        for cap in capabilities.unwrap_or_default() {
+            scanned.push((path.clone(), analyzer.language(path).to_string(), cap));
+        }
+    }
+
+    let capabilities: Vec<EmbeddedCapability> = evidence::attach_tests(scanned)
+        .iter()
+        .map(|(path, language, cap)| EmbeddedCapability::from_capability(cap, language, &path.display().to_string()))
+        .collect();
+    let mut kinds = BTreeMap::new();
+    for cap in &capabilities {
+        *kinds.entry(cap.kind.clone()).or_default() += 1;
+    }
+
+    Ok(RevisionScan {
+        revision: revision.to_string(),
+        commit: commit.id().to_string(),
+        committed_at: chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default(),
+        files_scanned: blobs.len(),
+        kinds,
+        capabilities,
+    })
+}
+
+/// Text of the file at `path` in a commit's tree, if it is there and UTF-8
+pub fn read_tree_file(repo: &Repository, tree: &Tree, path: &Path) -> Option<String> {
+    let blob = repo.find_blob(tree.get_path(path).ok()?.id()).ok()?;
+    String::from_utf8(blob.content().to_vec()).ok()
+}
+
+/// Path of the file named `name` next to `path` (a manifest's lockfile)
+pub fn sibling(path: &Path, name: &str) -> PathBuf {
+    path.parent().unwrap_or(Path::new("")).join(name)
+}
+
+/// Scan several revisions in turn, oldest first as given: a capability timeline
+pub fn scan_revisions(
+    repo: &Repository,
+    revisions: &[String],
+    shredder: &mut Shredder,
+    scannable: fn(&Path) -> bool,
+) -> Result<Vec<RevisionScan>> {
+    revisions
+        .iter()
+        .map(|revision| scan_revision(repo, revision, shredder, scannable))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest;
+    use git2::Signature;
+    use std::fs;
+
+    #[test]
+    fn test_scans_tagged_commits_without_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let signature = Signature::now("Dev", "dev@example.com").unwrap();
+        let commit_files = |files: &[(&str, &str)], tag: &str| {
+            let mut index = repo.index().unwrap();
+            for (path, content) in files {
+                fs::write(dir.path().join(path), content).unwrap();
+                index.add_path(Path::new(path)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            let id = repo.commit(Some("HEAD"), &signature, &signature, tag, &tree, &parents).unwrap();
+            repo.tag_lightweight(tag, &repo.find_object(id, None).unwrap(), false).unwrap();
+        };
+        commit_files(
+            &[
+                ("app.py", "def charge(amount):\n    return amount\n"),
+                ("Cargo.toml", "[package]\nname = \"shop\"\n\n[dependencies]\ntokio = \"1\"\n"),
+                ("Cargo.lock", "[[package]]\nname = \"tokio\"\nversion = \"1.35.1\"\n"),
+            ],
+            "v1.0",
+        );
+        commit_files(&[("app.py", "def charge(amount):\n    return amount\n\ndef refund(amount):\n    return -amount\n")], "v1.1");
+        // The working tree no longer matters
+        fs::write(dir.path().join("app.py"), "").unwrap();
+
+        let is_scannable = |path: &Path| manifest::is_manifest(path) || path.extension().map(|e| e == "py").unwrap_or(false);
+        let mut shredder = Shredder::new().unwrap();
+        let revisions = vec!["v1.0".to_string(), "v1.1".to_string()];
+        let timeline = scan_revisions(&repo, &revisions, &mut shredder, is_scannable).unwrap();
+
+        assert_eq!(timeline[0].revision, "v1.0");
+        assert_eq!(timeline[0].files_scanned, 2);
+        assert_eq!(timeline[0].kinds.get("function"), Some(&1));
+        let tokio = timeline[0].capabilities.iter().find(|c| c.kind == "dependency").unwrap();
+        assert_eq!(tokio.doc_comment.as_deref(), Some("Depends on tokio 1.35.1"));
+        assert_eq!(timeline[1].kinds.get("function"), Some(&2));
+        assert_eq!(timeline[1].capabilities[1].path, "app.py");
+        assert_ne!(timeline[0].commit, timeline[1].commit);
+    }
+}
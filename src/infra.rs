@@ -0,0 +1,446 @@
+use anyhow::Result;
+use regex::Regex;
+use std::path::Path;
+
+use crate::extractors::{brace_block_end, capability_from_span};
+use crate::shredder::{Capability, CapabilityKind};
+use crate::spans::{describe, indented_block_end, key_offset};
+
+/// Terraform provider prefixes worth naming in descriptions
+const PROVIDERS: &[(&str, &str)] = &[
+    ("aws", "AWS"),
+    ("google", "Google Cloud"),
+    ("azurerm", "Azure"),
+    ("kubernetes", "Kubernetes"),
+    ("helm", "Helm"),
+    ("cloudflare", "Cloudflare"),
+    ("digitalocean", "DigitalOcean"),
+    ("vercel", "Vercel"),
+    ("github", "GitHub"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InfraFormat {
+    Dockerfile,
+    Terraform,
+    Kubernetes,
+    HelmChart,
+    GitHubActions,
+    GitLabCi,
+}
+
+/// Whether a file may declare infrastructure: a Dockerfile, Terraform, a
+/// Helm chart, a CI workflow, or any other YAML (checked for Kubernetes
+/// objects when analyzed)
+pub fn is_infrastructure(path: &Path) -> bool {
+    infra_format(path).is_some()
+}
+
+/// Platform an infrastructure file's capabilities are reported under
+/// (`Dockerfile` -> "docker", `main.tf` -> "terraform")
+pub fn platform(path: &Path) -> Option<&'static str> {
+    infra_format(path).map(|format| match format {
+        InfraFormat::Dockerfile => "docker",
+        InfraFormat::Terraform => "terraform",
+        InfraFormat::Kubernetes => "kubernetes",
+        InfraFormat::HelmChart => "helm",
+        InfraFormat::GitHubActions => "github-actions",
+        InfraFormat::GitLabCi => "gitlab-ci",
+    })
+}
+
+fn infra_format(path: &Path) -> Option<InfraFormat> {
+    let file_name = path.file_name()?.to_str()?;
+    let lower = file_name.to_lowercase();
+    if lower == "dockerfile" || lower == "containerfile" || lower.starts_with("dockerfile.") || lower.ends_with(".dockerfile") {
+        return Some(InfraFormat::Dockerfile);
+    }
+    if lower == ".gitlab-ci.yml" {
+        return Some(InfraFormat::GitLabCi);
+    }
+    if file_name == "Chart.yaml" {
+        return Some(InfraFormat::HelmChart);
+    }
+    match path.extension()?.to_str()? {
+        "tf" => Some(InfraFormat::Terraform),
+        "yaml" | "yml" if path.parent().map(|p| p.ends_with(".github/workflows")).unwrap_or(false) => {
+            Some(InfraFormat::GitHubActions)
+        }
+        "yaml" | "yml" => Some(InfraFormat::Kubernetes),
+        _ => None,
+    }
+}
+
+/// THE INFRASTRUCTURE: Infrastructure capabilities - Docker build stages,
+/// Terraform resources and modules, Kubernetes objects, Helm charts and CI
+/// jobs - each with a generated description ("Terraform resource
+/// aws_s3_bucket.assets on AWS") as its doc comment. The format comes from
+/// `path`.
+pub fn analyze_source(path: &Path, source: &str) -> Result<Vec<Capability>> {
+    Ok(match infra_format(path) {
+        Some(InfraFormat::Dockerfile) => dockerfile(path, source),
+        Some(InfraFormat::Terraform) => terraform(source),
+        Some(InfraFormat::Kubernetes) => kubernetes(path, source),
+        Some(InfraFormat::HelmChart) => helm_chart(source)?,
+        Some(InfraFormat::GitHubActions) => github_actions(path, source)?,
+        Some(InfraFormat::GitLabCi) => gitlab_ci(source)?,
+        None => Vec::new(),
+    })
+}
+
+/// One capability per build stage (`FROM rust:1.75 AS builder`)
+fn dockerfile(path: &Path, source: &str) -> Vec<Capability> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("Dockerfile");
+    let from_regex = Regex::new(r"(?im)^[ \t]*FROM[ \t]+(?:--platform=\S+[ \t]+)?(\S+)(?:[ \t]+AS[ \t]+(\S+))?")
+        .expect("valid FROM regex");
+    let instruction_regex = Regex::new(r"(?im)^[ \t]*(EXPOSE|CMD|ENTRYPOINT|RUN)[ \t]+((?:.*\\\r?\n)*.*)")
+        .expect("valid instruction regex");
+
+    let stages: Vec<(usize, String, Option<String>)> = from_regex
+        .captures_iter(source)
+        .map(|caps| {
+            let whole = caps.get(0).expect("match");
+            let start = whole.start() + (whole.as_str().len() - whole.as_str().trim_start().len());
+            (start, caps[1].to_string(), caps.get(2).map(|a| a.as_str().to_string()))
+        })
+        .collect();
+
+    let mut capabilities = Vec::new();
+    for (i, (start, base, alias)) in stages.iter().enumerate() {
+        let next = stages.get(i + 1).map(|(s, _, _)| *s).unwrap_or(source.len());
+        let end = start + source[*start..next].trim_end().len();
+
+        let mut ports = Vec::new();
+        let mut command = None;
+        let mut tools: Vec<String> = Vec::new();
+        for caps in instruction_regex.captures_iter(&source[*start..end]) {
+            let argument = caps[2].replace("\\\n", " ").split_whitespace().collect::<Vec<_>>().join(" ");
+            match caps[1].to_uppercase().as_str() {
+                "EXPOSE" => ports.extend(argument.split_whitespace().map(str::to_string)),
+                "RUN" => {
+                    // First word of each chained command: `apt-get update && cargo build`
+                    for step in argument.split("&&") {
+                        if let Some(tool) = step.split_whitespace().next() {
+                            if !tools.iter().any(|t| t == tool) {
+                                tools.push(tool.to_string());
+                            }
+                        }
+                    }
+                }
+                _ => command = Some(argument),
+            }
+        }
+
+        let mut description = format!("Docker image built from {}", base);
+        if let Some(alias) = alias {
+            description.push_str(&format!(" (build stage {})", alias));
+        }
+        if !tools.is_empty() {
+            description.push_str(&format!("; build steps use {}", tools.join(", ")));
+        }
+        if !ports.is_empty() {
+            description.push_str(&format!("; exposes port {}", ports.join(", ")));
+        }
+        if let Some(command) = command {
+            description.push_str(&format!("; runs {}", command));
+        }
+
+        let name = alias.clone().unwrap_or_else(|| file_name.to_string());
+        let mut cap = capability_from_span(name, CapabilityKind::Infrastructure, source, *start, end);
+        cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+        capabilities.push(cap);
+    }
+    capabilities
+}
+
+/// One capability per `resource` and `module` block
+fn terraform(source: &str) -> Vec<Capability> {
+    let block_regex = Regex::new(r#"(?m)^[ \t]*(resource|module)[ \t]+"([^"]+)"(?:[ \t]+"([^"]+)")?[ \t]*\{"#)
+        .expect("valid block regex");
+    let source_regex = Regex::new(r#"(?m)^[ \t]*source[ \t]*=[ \t]*"([^"]+)""#).expect("valid source regex");
+
+    let mut capabilities = Vec::new();
+    for caps in block_regex.captures_iter(source) {
+        let start = caps.get(1).map(|m| m.start()).unwrap_or(0);
+        let end = brace_block_end(source, start);
+
+        let (name, description) = match (&caps[1], caps.get(3)) {
+            ("resource", Some(resource_name)) => {
+                let resource_type = &caps[2];
+                let address = format!("{}.{}", resource_type, resource_name.as_str());
+                let prefix = resource_type.split('_').next().unwrap_or(resource_type);
+                let provider = PROVIDERS
+                    .iter()
+                    .find(|(p, _)| *p == prefix)
+                    .map(|(_, label)| *label)
+                    .unwrap_or(prefix);
+                let description = format!("Terraform resource {} on {}", address, provider);
+                (address, description)
+            }
+            ("module", _) => {
+                let name = caps[2].to_string();
+                let description = match source_regex.captures(&source[start..end]) {
+                    Some(module_source) => format!("Terraform module {} from {}", name, &module_source[1]),
+                    None => format!("Terraform module {}", name),
+                };
+                (name, description)
+            }
+            _ => continue,
+        };
+        let mut cap = capability_from_span(name, CapabilityKind::Infrastructure, source, start, end);
+        cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+        capabilities.push(cap);
+    }
+    capabilities
+}
+
+/// One capability per Kubernetes object (`---`-separated documents with an
+/// `apiVersion` and `kind`). Read line by line rather than parsed, so Helm
+/// templates with `{{ }}` placeholders work too.
+fn kubernetes(path: &Path, source: &str) -> Vec<Capability> {
+    let kind_regex = Regex::new(r#"(?m)^kind:[ \t]*["']?(\w+)"#).expect("valid kind regex");
+    let image_regex = Regex::new(r#"(?m)^[ \t]*(?:-[ \t]+)?image:[ \t]*["']?([^"'\s]+)"#).expect("valid image regex");
+    let port_regex = Regex::new(r"(?m)^[ \t]*(?:-[ \t]+)?containerPort:[ \t]*(\d+)").expect("valid port regex");
+    let helm_template = path.components().any(|c| c.as_os_str() == "templates");
+
+    // Byte ranges of the `---`-separated documents
+    let mut documents = Vec::new();
+    let (mut doc_start, mut offset) = (0, 0);
+    for line in source.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            documents.push((doc_start, offset));
+            doc_start = offset + line.len();
+        }
+        offset += line.len();
+    }
+    documents.push((doc_start, source.len()));
+
+    let mut capabilities = Vec::new();
+    for (doc_offset, doc_end) in documents {
+        let doc = &source[doc_offset..doc_end];
+        let kind = match kind_regex.captures(doc) {
+            Some(kind) if doc.contains("apiVersion:") => kind[1].to_string(),
+            _ => continue,
+        };
+        let metadata = key_offset(doc, "metadata", 0).map(|m| (m, indented_block_end(doc, m)));
+        let field = |key: &str| {
+            let (from, to) = metadata?;
+            let at = key_offset(&doc[..to], key, from)?;
+            let value = doc[at..].lines().next()?.split_once(':')?.1.trim().trim_matches(['"', '\'']);
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let name = field("name").unwrap_or_else(|| kind.clone());
+
+        let mut description = format!("Kubernetes {} {}", kind, name);
+        if helm_template {
+            description = format!("Helm template for {}", description);
+        }
+        if let Some(namespace) = field("namespace") {
+            description.push_str(&format!(" in namespace {}", namespace));
+        }
+        let images: Vec<&str> = image_regex.captures_iter(doc).filter_map(|c| c.get(1)).map(|c| c.as_str()).collect();
+        if !images.is_empty() {
+            description.push_str(&format!(" running {}", images.join(", ")));
+        }
+        let ports: Vec<&str> = port_regex.captures_iter(doc).filter_map(|c| c.get(1)).map(|c| c.as_str()).collect();
+        if !ports.is_empty() {
+            description.push_str(&format!(" on port {}", ports.join(", ")));
+        }
+
+        let trimmed = doc.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let start = doc_offset + doc.find(trimmed).unwrap_or(0);
+        let end = start + trimmed.len();
+        let mut cap = capability_from_span(name.clone(), CapabilityKind::Infrastructure, source, start, end);
+        cap.qualified_name = format!("{}/{}", kind, name);
+        cap.doc_comment = Some(describe(description, None));
+        capabilities.push(cap);
+    }
+    capabilities
+}
+
+/// A Helm chart's `Chart.yaml`: the chart itself
+fn helm_chart(source: &str) -> Result<Vec<Capability>> {
+    let chart: serde_yaml::Value = serde_yaml::from_str(source)?;
+    let name = match chart.get("name").and_then(|n| n.as_str()) {
+        Some(name) => name.to_string(),
+        None => return Ok(Vec::new()),
+    };
+    let mut description = format!("Helm chart {}", name);
+    if let Some(summary) = chart.get("description").and_then(|d| d.as_str()) {
+        description.push_str(&format!(": {}", summary.trim()));
+    }
+    let mut cap = capability_from_span(name, CapabilityKind::Infrastructure, source, 0, source.trim_end().len());
+    cap.doc_comment = Some(describe(description, None));
+    Ok(vec![cap])
+}
+
+/// One capability per job of a GitHub Actions workflow
+fn github_actions(path: &Path, source: &str) -> Result<Vec<Capability>> {
+    let workflow: serde_yaml::Value = serde_yaml::from_str(source)?;
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("workflow");
+    let workflow_name = workflow.get("name").and_then(|n| n.as_str()).unwrap_or(stem);
+    let triggers: Vec<String> = match workflow.get("on") {
+        Some(serde_yaml::Value::String(event)) => vec![event.clone()],
+        Some(serde_yaml::Value::Sequence(events)) => events.iter().filter_map(|e| e.as_str()).map(str::to_string).collect(),
+        Some(serde_yaml::Value::Mapping(events)) => events.keys().filter_map(|e| e.as_str()).map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    let jobs = match workflow.get("jobs").and_then(|j| j.as_mapping()) {
+        Some(jobs) => jobs,
+        None => return Ok(Vec::new()),
+    };
+    let jobs_offset = key_offset(source, "jobs", 0).unwrap_or(0);
+
+    let mut capabilities = Vec::new();
+    for (id, job) in jobs {
+        let id = match id.as_str() {
+            Some(id) => id,
+            None => continue,
+        };
+        let steps = job.get("steps").and_then(|s| s.as_sequence());
+        let actions: Vec<&str> = steps
+            .into_iter()
+            .flatten()
+            .filter_map(|step| step.get("uses")?.as_str())
+            .map(|action| action.split('@').next().unwrap_or(action))
+            .collect();
+        let commands: Vec<&str> = steps
+            .into_iter()
+            .flatten()
+            .filter_map(|step| step.get("run")?.as_str())
+            .filter_map(|run| run.lines().map(str::trim).find(|l| !l.is_empty()))
+            .collect();
+
+        let mut description = format!("GitHub Actions job {} in workflow {}", id, workflow_name);
+        if !triggers.is_empty() {
+            description.push_str(&format!(" (on {})", triggers.join(", ")));
+        }
+        if !actions.is_empty() {
+            description.push_str(&format!(": uses {}", actions.join(", ")));
+        }
+        if !commands.is_empty() {
+            let separator = if actions.is_empty() { ":" } else { ";" };
+            description.push_str(&format!("{} runs {}", separator, commands.join(", ")));
+        }
+
+        let (start, end) = key_offset(source, id, jobs_offset)
+            .map(|start| (start, indented_block_end(source, start)))
+            .unwrap_or((0, source.trim_end().len()));
+        let mut cap = capability_from_span(id.to_string(), CapabilityKind::Infrastructure, source, start, end);
+        cap.qualified_name = format!("{}.{}", stem, id);
+        cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+        capabilities.push(cap);
+    }
+    Ok(capabilities)
+}
+
+/// One capability per job (a top-level entry with a `script`) of
+/// `.gitlab-ci.yml`; hidden `.template` jobs are skipped
+fn gitlab_ci(source: &str) -> Result<Vec<Capability>> {
+    let pipeline: serde_yaml::Value = serde_yaml::from_str(source)?;
+    let entries = match pipeline.as_mapping() {
+        Some(entries) => entries,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut capabilities = Vec::new();
+    for (name, job) in entries {
+        let name = match name.as_str() {
+            Some(name) if !name.starts_with('.') => name,
+            _ => continue,
+        };
+        let script: Vec<&str> = match job.get("script") {
+            Some(serde_yaml::Value::String(line)) => vec![line.as_str()],
+            Some(serde_yaml::Value::Sequence(lines)) => lines.iter().filter_map(|l| l.as_str()).collect(),
+            _ => continue,
+        };
+
+        let mut description = format!("GitLab CI job {}", name);
+        if let Some(stage) = job.get("stage").and_then(|s| s.as_str()) {
+            description.push_str(&format!(" (stage {})", stage));
+        }
+        description.push_str(&format!(": runs {}", script.join(", ")));
+
+        let (start, end) = key_offset(source, name, 0)
+            .map(|start| (start, indented_block_end(source, start)))
+            .unwrap_or((0, source.trim_end().len()));
+        let mut cap = capability_from_span(name.to_string(), CapabilityKind::Infrastructure, source, start, end);
+        cap.doc_comment = Some(describe(description, cap.doc_comment.take()));
+        capabilities.push(cap);
+    }
+    Ok(capabilities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infrastructure_capabilities() {
+        let summary = |path: &str, source: &str| -> Vec<(String, usize, String)> {
+            analyze_source(Path::new(path), source)
+                .unwrap()
+                .into_iter()
+                .map(|c| (c.qualified_name, c.line, c.doc_comment.unwrap_or_default()))
+                .collect()
+        };
+
+        let dockerfile = "FROM rust:1.75 AS builder\nRUN apt-get update && \\\n    cargo build --release\n\nFROM debian:bookworm-slim\nEXPOSE 8080\nCMD [\"/app/server\"]\n";
+        assert_eq!(
+            summary("deploy/Dockerfile", dockerfile),
+            vec![
+                (
+                    "builder".to_string(),
+                    1,
+                    "Docker image built from rust:1.75 (build stage builder); build steps use apt-get, cargo".to_string()
+                ),
+                (
+                    "Dockerfile".to_string(),
+                    5,
+                    "Docker image built from debian:bookworm-slim; exposes port 8080; runs [\"/app/server\"]".to_string()
+                ),
+            ]
+        );
+
+        let terraform = "# Static assets\nresource \"aws_s3_bucket\" \"assets\" {\n  bucket = \"shop-assets\"\n}\n\nmodule \"vpc\" {\n  source = \"terraform-aws-modules/vpc/aws\"\n}\n\nvariable \"region\" {}\n";
+        assert_eq!(
+            summary("infra/main.tf", terraform),
+            vec![
+                (
+                    "aws_s3_bucket.assets".to_string(),
+                    2,
+                    "Terraform resource aws_s3_bucket.assets on AWS\nStatic assets".to_string()
+                ),
+                ("vpc".to_string(), 6, "Terraform module vpc from terraform-aws-modules/vpc/aws".to_string()),
+            ]
+        );
+
+        let kubernetes = "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: web\n  namespace: prod\nspec:\n  template:\n    spec:\n      containers:\n        - name: app\n          image: nginx:1.25\n          ports:\n            - containerPort: 80\n---\napiVersion: v1\nkind: Service\nmetadata:\n  name: web\n";
+        assert_eq!(
+            summary("k8s/web.yaml", kubernetes),
+            vec![
+                (
+                    "Deployment/web".to_string(),
+                    1,
+                    "Kubernetes Deployment web in namespace prod running nginx:1.25 on port 80".to_string()
+                ),
+                ("Service/web".to_string(), 15, "Kubernetes Service web".to_string()),
+            ]
+        );
+        assert!(summary("docker-compose.yml", "services:\n  web:\n    image: nginx\n").is_empty());
+
+        let workflow = "name: CI\non: [push, pull_request]\njobs:\n  test:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/checkout@v4\n      - run: cargo test\n";
+        assert_eq!(
+            summary(".github/workflows/ci.yml", workflow),
+            vec![(
+                "ci.test".to_string(),
+                4,
+                "GitHub Actions job test in workflow CI (on push, pull_request): uses actions/checkout; runs cargo test"
+                    .to_string()
+            )]
+        );
+    }
+}
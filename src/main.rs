@@ -1,17 +1,35 @@
 mod shredder;
+mod extractors;
 mod vectorizer;
 mod summarizer;
 mod matchmaker;
 mod deployer;
 mod github;
 mod scraper;
+mod analyzer;
 mod authorship;
+mod cache;
+mod evidence;
+mod graph;
+mod history;
+mod infra;
+mod contracts;
+mod delta;
+mod manifest;
+mod metrics;
+mod notebook;
+mod profile;
+mod readme;
+mod scan_manifest;
+mod spans;
+mod workspace;
 
 use clap::Parser;
 use colored::*;
 use git2::{Config, Repository};
 use ignore::WalkBuilder;
-use shredder::{CapabilityKind, Shredder};
+use shredder::Shredder;
+use extractors::{CustomQueries, ExtractorRegistry};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use anyhow::Result;
@@ -24,6 +42,12 @@ use summarizer::Summarizer;
 use matchmaker::{Matchmaker, Need};
 use deployer::Deployer;
 use scraper::BountyScraper;
+use analyzer::Analyzer;
+use authorship::Identity;
+use cache::EmbeddingCache;
+use workspace::Workspace;
+use profile::ContributorProfile;
+use scan_manifest::ScanManifest;
 
 /// ECHEO: The Resonant Engine
 /// Scans local code capabilities to match market signals.
@@ -121,16 +145,55 @@ struct Args {
     /// Auto-embed scraped bounties
     #[arg(long)]
     auto_embed_scraped: bool,
+
+    /// Directory of extra tree-sitter queries, one `<language>.scm` per language
+    /// (defaults to .echeo/queries in the scanned directory, if present)
+    #[arg(long)]
+    query_dir: Option<String>,
+
+    /// Re-shred every file instead of reusing unchanged files from .echeo/scan_manifest.json
+    #[arg(long)]
+    full_scan: bool,
+
+    /// Only report capabilities added, modified or removed since this revision
+    /// (or in a range like `v1.0..v1.1`), as JSON on stdout
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Revision to compare `--since` against (defaults to the working tree)
+    #[arg(long)]
+    until: Option<String>,
+
+    /// Scan the repository as of these commits or tags (comma-separated or
+    /// repeated), without checking them out, as a JSON timeline on stdout
+    #[arg(long, value_delimiter = ',')]
+    at: Vec<String>,
+
+    /// Another of your git identities for authorship, as `Name <email>` or an
+    /// email (repeatable; user.name/user.email and any echeo.identity git
+    /// config entries are always included)
+    #[arg(long)]
+    identity: Vec<String>,
+
+    /// Write .echeo/profile.json: how much of each repository, language, kind
+    /// and package you authored
+    #[arg(long)]
+    profile: bool,
 }
 
 // The "High Value" Target List
 const HIGH_VALUE_EXTENSIONS: &[&str] = &[
-    "ts", "tsx", // TypeScript (The Industry Standard)
-    "rs",        // Rust (The Weapon)
-    "go",        // Go (The Systems)
-    "py",        // Python (The Brains)
-    "sol",       // Solidity (The Crypto)
-    "rb",        // Ruby (The Legacy)
+    "ts", "tsx", "mts", "cts", // TypeScript (The Industry Standard)
+    "js", "jsx", "mjs", "cjs", // JavaScript (The Web)
+    "vue", "svelte",           // Single-file components (The Frontend)
+    "rs",                      // Rust (The Weapon)
+    "go",                      // Go (The Systems)
+    "py",                      // Python (The Brains)
+    "sol",                     // Solidity (The Crypto)
+    "rb",                      // Ruby (The Legacy)
+    "java", "kt",              // JVM (The Enterprise)
+    "cs",                      // C# (The .NET)
+    "ipynb",                   // Jupyter notebooks (The Lab)
 ];
 
 #[tokio::main]
@@ -138,6 +201,33 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     let root_path = Path::new(&args.path);
 
+    // Delta and historical scans read revisions from git: JSON only, no sweep
+    if args.since.is_some() || !args.at.is_empty() {
+        let query_dir = query_dir(args.query_dir.as_deref(), root_path);
+        let custom_queries = match query_dir {
+            Some(dir) => CustomQueries::load_dir(&dir, &ExtractorRegistry::default())?,
+            None => CustomQueries::default(),
+        };
+        let repo = Repository::discover(root_path)?;
+        let mut shredder = Shredder::new()?.with_queries(custom_queries);
+        let output = match &args.since {
+            Some(since) => serde_json::to_string_pretty(&delta::capability_delta(
+                &repo,
+                since,
+                args.until.as_deref(),
+                &mut shredder,
+            )?)?,
+            None => serde_json::to_string_pretty(&history::scan_revisions(
+                &repo,
+                &args.at,
+                &mut shredder,
+                is_scannable,
+            )?)?,
+        };
+        println!("{}", output);
+        return Ok(());
+    }
+
     // ECHEO Boot Sequence
     print_echeo_banner();
     println!("{}", "INITIATING ACTIVE SONAR SWEEP...".bright_cyan().bold());
@@ -177,6 +267,7 @@ async fn main() -> Result<()> {
     let file_count = AtomicUsize::new(0);
     let high_value_count = AtomicUsize::new(0);
     let capability_count = AtomicUsize::new(0);
+    let unchanged_count = AtomicUsize::new(0);
 
     // 1. THE CRAWLER
     // WalkBuilder respects .gitignore automatically.
@@ -194,57 +285,125 @@ async fn main() -> Result<()> {
         .map(|e| e.path().to_owned())
         .collect();
 
+    // Package boundaries of monorepos and workspaces
+    let workspace = Workspace::discover(&entries);
+
+    // Custom queries extend the built-in extractors
+    let query_dir = query_dir(args.query_dir.as_deref(), root_path);
+    let custom_queries = match &query_dir {
+        Some(dir) => {
+            let queries = CustomQueries::load_dir(dir, &ExtractorRegistry::default())?;
+            println!(
+                "{} Loaded {} custom queries from {}",
+                "[SHREDDER]".bright_cyan(),
+                queries.len().to_string().cyan(),
+                dir.display()
+            );
+            queries
+        }
+        None => CustomQueries::default(),
+    };
+
+    // Git repositories the scanned files belong to, opened once per worker
+    // thread for blame (rather than per file), see `SHREDDERS` below
+    let git_repos = git_repositories(root_path, &entries);
+    let identities = user_identities(&args.identity);
+
+    // What the previous scan extracted from each file, as long as the queries
+    // and what blame depends on are unchanged
+    let fingerprint = scan_manifest::fingerprint(query_dir.as_deref(), &git_repos, &identities);
+    let scan_manifest_path = root_path.join(".echeo").join("scan_manifest.json");
+    let previous_scan = if args.full_scan {
+        ScanManifest::default()
+    } else {
+        ScanManifest::load(&scan_manifest_path, &fingerprint)
+    };
+    let file_hashes = Mutex::new(HashMap::new());
+
     // Collect all capabilities for batch embedding (thread-safe)
     let all_capabilities = Mutex::new(Vec::new());
 
     // 3. THE SCAN (Parallelized)
+    // Each worker thread keeps one Shredder (parser + repository handle) per
+    // repository for the whole scan, so blame caches (the change index,
+    // reformat commits) are shared by all the files it scans.
+    thread_local! {
+        static SHREDDERS: std::cell::RefCell<HashMap<Option<PathBuf>, Shredder>> = Default::default();
+    }
     entries.par_iter().for_each(|path| {
         if path.is_file() {
             file_count.fetch_add(1, Ordering::Relaxed);
-            
-            if let Some(ext) = path.extension() {
-                if let Some(ext_str) = ext.to_str() {
-                    if HIGH_VALUE_EXTENSIONS.contains(&ext_str) {
-                        high_value_count.fetch_add(1, Ordering::Relaxed);
-                        
-                        // THE SHREDDER: Extract capabilities from AST
-                        // Try to enable authorship tracking if we're in a git repo
-                        let mut shredder = if let Ok(repo_path) = find_git_repo(path) {
-                            // Try to get user email/name from git config
-                            let user_email = get_git_config("user.email").ok();
-                            let user_name = get_git_config("user.name").ok();
-                            Shredder::with_authorship(&repo_path, user_email, user_name)
+            if !is_scannable(path) {
+                return;
+            }
+
+            // Files are analyzed by path; one Shredder per repository blames source files
+            let analyzer = Analyzer::for_path(path);
+            let language = analyzer.language(path);
+            let repo_path = git_repos
+                .iter()
+                .find(|(prefix, _)| path.starts_with(prefix))
+                .map(|(_, repo_path)| repo_path.clone());
+            SHREDDERS.with_borrow_mut(|shredders| {
+                let shredder = shredders.entry(repo_path).or_insert_with_key(|repo_path| {
+                    match repo_path {
+                        Some(repo_path) => {
+                            Shredder::with_authorship(repo_path, &identities)
                                 .unwrap_or_else(|_| Shredder::new().unwrap())
-                        } else {
-                            Shredder::new().unwrap()
-                        };
-
-                        match shredder.shred_file(path) {
-                            Ok(capabilities) => {
-                                if !capabilities.is_empty() {
-                                    capability_count.fetch_add(capabilities.len(), Ordering::Relaxed);
-                                    
-                                    // Store for batch embedding
-                                    if let Ok(mut caps) = all_capabilities.lock() {
-                                        for cap in capabilities {
-                                            caps.push((path.clone(), ext_str.to_string(), cap));
-                                        }
-                                    }
-                                    
-                                    // Show file detection (embeddings will be shown later)
-                                    print_detection(path, ext_str);
-                                } else {
-                                    print_detection(path, ext_str);
+                        }
+                        None => Shredder::new().unwrap(),
+                    }
+                    .with_queries(custom_queries.clone())
+                });
+
+                // THE SCAN MANIFEST: Unchanged files keep the previous scan's capabilities
+                if let Some(hash) = scan_manifest::file_hash(path) {
+                    let blame_key = match analyzer {
+                        Analyzer::Shredder => shredder.blame_key(path),
+                        _ => None,
+                    };
+                    if let Some(entry) = previous_scan.unchanged(path, &hash, blame_key.as_deref()) {
+                        unchanged_count.fetch_add(1, Ordering::Relaxed);
+                        if !entry.capabilities.is_empty() {
+                            high_value_count.fetch_add(1, Ordering::Relaxed);
+                            capability_count.fetch_add(entry.capabilities.len(), Ordering::Relaxed);
+                            if let Ok(mut caps) = all_capabilities.lock() {
+                                for cap in &entry.capabilities {
+                                    caps.push((path.clone(), entry.extension.clone(), cap.clone()));
                                 }
                             }
-                            Err(_) => {
-                                // If parsing fails, just show the file
-                                print_detection(path, ext_str);
-                            }
                         }
+                        if let Ok(mut hashes) = file_hashes.lock() {
+                            hashes.insert(path.clone(), (hash, blame_key));
+                        }
+                        return;
+                    }
+                    if let Ok(mut hashes) = file_hashes.lock() {
+                        hashes.insert(path.clone(), (hash, blame_key));
                     }
                 }
-            }
+
+                // Every source file counts as a signal, other files only when
+                // they yield capabilities
+                let label = match analyzer {
+                    Analyzer::Manifest => "manifest",
+                    Analyzer::Contract => "contract",
+                    Analyzer::Readme => "readme",
+                    Analyzer::Infrastructure => "infra",
+                    Analyzer::Shredder => language,
+                };
+                let capabilities = analyzer.analyze_file(shredder, path).unwrap_or_default();
+                if !capabilities.is_empty() || analyzer == Analyzer::Shredder {
+                    high_value_count.fetch_add(1, Ordering::Relaxed);
+                    capability_count.fetch_add(capabilities.len(), Ordering::Relaxed);
+                    if let Ok(mut caps) = all_capabilities.lock() {
+                        for cap in capabilities {
+                            caps.push((path.clone(), language.to_string(), cap));
+                        }
+                    }
+                    print_detection(path, label);
+                }
+            });
         }
     });
 
@@ -261,9 +420,57 @@ async fn main() -> Result<()> {
         capability_count.load(Ordering::Relaxed).to_string().bright_cyan().bold(),
         "CAPABILITIES".bright_cyan().bold()
     );
+    let unchanged = unchanged_count.load(Ordering::Relaxed);
+    if unchanged > 0 {
+        println!(
+            "UNCHANGED: {} files reused from {}",
+            unchanged.to_string().white(),
+            scan_manifest_path.display()
+        );
+    }
 
     // 4. THE VECTORIZER: Generate embeddings if enabled
-    let all_caps = all_capabilities.into_inner().unwrap();
+    let scanned_caps = all_capabilities.into_inner().unwrap();
+
+    // Remember what each file yielded, so the next scan can skip unchanged files
+    let scan = ScanManifest::from_scan(&fingerprint, file_hashes.into_inner().unwrap(), &scanned_caps);
+    if let Err(e) = scan.save(&scan_manifest_path) {
+        println!("{} Failed to save scan manifest: {}", "[ERROR]".red(), e);
+    }
+
+    let all_caps = evidence::attach_tests(scanned_caps);
+    let capability_graph = graph::CapabilityGraph::build(&all_caps);
+
+    // THE PROFILE: The user's authored footprint across the tree
+    if args.profile {
+        let mut profiled: Vec<_> = all_caps
+            .iter()
+            .map(|(path, ext, cap)| EmbeddedCapability::from_capability(cap, ext, &path.display().to_string()))
+            .collect();
+        workspace.annotate(&mut profiled);
+        let profile = ContributorProfile::build(&profiled, &git_repos);
+        let profile_path = Path::new(".echeo").join("profile.json");
+        fs::create_dir_all(".echeo")?;
+        fs::write(&profile_path, serde_json::to_string_pretty(&profile)?)?;
+        println!("{}", "---------------------------------".dimmed());
+        println!(
+            "{} You authored {} of {} capabilities ({:.0} lines)",
+            "[PROFILE]".bright_yellow(),
+            profile.authored.to_string().bright_cyan(),
+            profile.capabilities.to_string().bright_cyan(),
+            profile.authored_lines
+        );
+        for language in profile.languages.iter().filter(|l| l.authored > 0).take(5) {
+            println!(
+                "  {} {}: {:.0}% of your lines, {:.0}% of its lines",
+                "[STACK]".dimmed(),
+                language.name.white(),
+                language.share * 100.0,
+                language.contribution
+            );
+        }
+        println!("{} Generated profile.json at {}", "[PROFILE]".bright_yellow(), profile_path.display().to_string().cyan());
+    }
     let mut embedded_caps: Option<Vec<EmbeddedCapability>> = None;
     
     if let Some(v) = &vectorizer {
@@ -272,34 +479,26 @@ async fn main() -> Result<()> {
             println!("{} Generating embeddings...", "[VECTORIZER]".bright_cyan());
             
             // Prepare capabilities for embedding
-            let embedding_tasks: Vec<_> = all_caps
+            let mut embedding_tasks: Vec<_> = all_caps
                 .iter()
-                .map(|(path, ext, cap)| {
-                    let kind_str = match cap.kind {
-                        CapabilityKind::Function => "function",
-                        CapabilityKind::Class => "class",
-                        CapabilityKind::Component => "component",
-                        CapabilityKind::ApiRoute => "api_route",
-                    };
-                    (
-                        cap.name.clone(),
-                        cap.code_snippet.clone(),
-                        ext.clone(),
-                        kind_str.to_string(),
-                        path.display().to_string(),
-                        cap.line,
-                        cap.authorship.clone(), // Include authorship info
-                    )
-                })
+                .map(|(path, ext, cap)| EmbeddedCapability::from_capability(cap, ext, &path.display().to_string()))
                 .collect();
-
-            match v.embed_capabilities(embedding_tasks).await {
-                Ok(embedded) => {
+            capability_graph.annotate(&mut embedding_tasks);
+            workspace.annotate(&mut embedding_tasks);
+
+            // Capabilities embedded by an earlier scan keep their embedding
+            let mut cache = EmbeddingCache::new(root_path)?;
+            match v.embed_capabilities_cached(embedding_tasks, &mut cache).await {
+                Ok((embedded, reused)) => {
+                    if let Err(e) = cache.save() {
+                        println!("{} Failed to save embedding cache: {}", "[ERROR]".red(), e);
+                    }
                     println!(
-                        "{} Generated {} embeddings ({} dimensions each)",
+                        "{} Generated {} embeddings ({} dimensions each, {} reused)",
                         "[VECTORIZER]".bright_green(),
                         embedded.len().to_string().bright_cyan(),
-                        embedded.first().map(|e| e.embedding.len()).unwrap_or(0).to_string().bright_cyan()
+                        embedded.first().map(|e| e.embedding.len()).unwrap_or(0).to_string().bright_cyan(),
+                        reused.to_string().bright_cyan()
                     );
                     
                     // Show sample embeddings
@@ -379,17 +578,11 @@ async fn main() -> Result<()> {
             let summary_tasks: Vec<_> = all_caps
                 .iter()
                 .map(|(_, ext, cap)| {
-                    let kind_str = match cap.kind {
-                        CapabilityKind::Function => "function",
-                        CapabilityKind::Class => "class",
-                        CapabilityKind::Component => "component",
-                        CapabilityKind::ApiRoute => "api_route",
-                    };
                     (
                         cap.name.clone(),
                         cap.code_snippet.clone(),
                         ext.clone(),
-                        kind_str.to_string(),
+                        cap.kind.as_str().to_string(),
                     )
                 })
                 .collect();
@@ -643,37 +836,35 @@ async fn main() -> Result<()> {
                 let mut needs = scraper.convert_to_needs(scraped_bounties);
 
                 // Auto-embed if requested
-                if args.auto_embed_scraped && !args.skip_embeddings {
-                    if vectorizer.is_some() {
-                        println!("{} Auto-embedding scraped bounties...", "[SCRAPER]".bright_cyan());
-                        
-                        let client = reqwest::Client::builder()
-                            .timeout(std::time::Duration::from_secs(30))
-                            .build()
-                            .expect("Failed to create HTTP client");
-                        let url = args.ollama_url.clone();
-                        let model = args.ollama_model.clone();
-
-                        for need in &mut needs {
-                            match Vectorizer::embed_single(
-                                client.clone(),
-                                url.clone(),
-                                model.clone(),
-                                need.title.clone(),
-                                need.description.clone(),
-                                "need".to_string(),
-                                "bounty".to_string(),
-                                need.id.clone(),
-                                0,
-                            )
-                            .await
-                            {
-                                Ok(embedded) => {
-                                    need.embedding = embedded.embedding;
-                                }
-                                Err(e) => {
-                                    eprintln!("Warning: Failed to embed need {}: {}", need.id, e);
-                                }
+                if args.auto_embed_scraped && !args.skip_embeddings && vectorizer.is_some() {
+                    println!("{} Auto-embedding scraped bounties...", "[SCRAPER]".bright_cyan());
+                    
+                    let client = reqwest::Client::builder()
+                        .timeout(std::time::Duration::from_secs(30))
+                        .build()
+                        .expect("Failed to create HTTP client");
+                    let url = args.ollama_url.clone();
+                    let model = args.ollama_model.clone();
+
+                    for need in &mut needs {
+                        match Vectorizer::embed_single(
+                            client.clone(),
+                            url.clone(),
+                            model.clone(),
+                            need.title.clone(),
+                            need.description.clone(),
+                            "need".to_string(),
+                            "bounty".to_string(),
+                            need.id.clone(),
+                            0,
+                        )
+                        .await
+                        {
+                            Ok(embedded) => {
+                                need.embedding = embedded.embedding;
+                            }
+                            Err(e) => {
+                                eprintln!("Warning: Failed to embed need {}: {}", need.id, e);
                             }
                         }
                     }
@@ -722,22 +913,68 @@ async fn main() -> Result<()> {
 
     // 9. GENERATE LOADOUT: Create loadout.json file
     if args.generate_loadout {
+        // The dependency graph doesn't need embeddings
+        let graph_path = Path::new(".echeo").join("graph.json");
+        fs::create_dir_all(".echeo")?;
+        fs::write(&graph_path, serde_json::to_string_pretty(&capability_graph)?)?;
+        println!("{} Generated graph.json at {}", "[GRAPH]".bright_yellow(), graph_path.display().to_string().cyan());
+
         if let Some(ref caps) = embedded_caps {
             let loadout = serde_json::json!({
                 "user_handle": "local_ghost",
                 "ship_velocity_score": 94,
-                "stack_dominance": {
-                    "typescript": 0.85,
-                    "rust": 0.12,
-                    "python": 0.03
-                },
+                "stack_dominance": ContributorProfile::build(caps, &git_repos).stack_dominance(),
+                "packages": workspace.packages(),
                 "armory": caps.iter().map(|cap| {
-                    serde_json::json!({
+                    let mut entry = serde_json::json!({
                         "name": cap.name,
                         "path": cap.path,
                         "confidence": 0.98,
                         "tags": [cap.language.clone(), cap.kind.clone()]
-                    })
+                    });
+                    for (key, value) in [
+                        ("qualified_name", &cap.qualified_name),
+                        ("signature", &cap.signature),
+                        ("doc_comment", &cap.doc_comment),
+                        ("visibility", &cap.visibility),
+                        ("first_commit_at", &cap.first_commit_at),
+                        ("last_commit_at", &cap.last_commit_at),
+                        ("user_last_touched_at", &cap.user_last_touched_at),
+                    ] {
+                        if let Some(value) = value {
+                            entry[key] = serde_json::Value::String(value.clone());
+                        }
+                    }
+                    if let Some(count) = cap.commit_count {
+                        entry["commit_count"] = serde_json::Value::from(count);
+                    }
+                    if let Some(end_line) = cap.end_line {
+                        entry["lines"] = serde_json::json!([cap.line, end_line]);
+                    }
+                    if let Some((start, end)) = cap.byte_range {
+                        entry["byte_range"] = serde_json::json!([start, end]);
+                    }
+                    if let Some(cell) = cap.cell {
+                        entry["cell"] = serde_json::Value::from(cell);
+                    }
+                    if let (Some(package), Some(root)) = (&cap.package_name, &cap.package_root) {
+                        entry["package"] = serde_json::Value::String(package.clone());
+                        entry["package_root"] = serde_json::Value::String(root.clone());
+                    }
+                    if let (Some(method), Some(route)) = (&cap.http_method, &cap.route_path) {
+                        entry["http_method"] = serde_json::Value::String(method.clone());
+                        entry["route_path"] = serde_json::Value::String(route.clone());
+                    }
+                    if !cap.tests.is_empty() {
+                        entry["tests"] = serde_json::json!(cap.tests);
+                    }
+                    if let Some(weight) = cap.weight {
+                        entry["weight"] = serde_json::Value::from(weight);
+                    }
+                    if let Some(metrics) = &cap.metrics {
+                        entry["metrics"] = serde_json::json!(metrics);
+                    }
+                    entry
                 }).collect::<Vec<_>>()
             });
 
@@ -762,16 +999,8 @@ async fn main() -> Result<()> {
             println!("{}", "---------------------------------".dimmed());
             println!("{} Uploading {} capabilities to Echeo API...", "[UPLOAD]".bright_cyan(), caps.len().to_string().cyan());
 
-            // Calculate stack dominance
-            let mut stack_counts: HashMap<String, usize> = HashMap::new();
-            for cap in caps.iter() {
-                *stack_counts.entry(cap.language.clone()).or_insert(0) += 1;
-            }
-            let total = caps.len() as f64;
-            let stack_dominance: HashMap<String, f64> = stack_counts
-                .into_iter()
-                .map(|(lang, count)| (lang, count as f64 / total))
-                .collect();
+            // Calculate stack dominance from the lines the user authored
+            let stack_dominance = ContributorProfile::build(caps, &git_repos).stack_dominance();
 
             // Calculate ship velocity score (average of all match scores, or default)
             let ship_velocity_score = 85.0; // Could calculate from matches if available
@@ -807,6 +1036,70 @@ async fn main() -> Result<()> {
                 if let Some(percentage) = cap.contribution_percentage {
                     json["contribution_percentage"] = serde_json::Value::Number(serde_json::Number::from_f64(percentage).unwrap_or(serde_json::Number::from(100)));
                 }
+                if let Some(ref contributors) = cap.contributors {
+                    json["contributors"] = serde_json::to_value(contributors).unwrap_or_default();
+                }
+                if let Some(ref first) = cap.first_commit_at {
+                    json["first_commit_at"] = serde_json::Value::String(first.clone());
+                }
+                if let Some(ref last) = cap.last_commit_at {
+                    json["last_commit_at"] = serde_json::Value::String(last.clone());
+                }
+                if let Some(count) = cap.commit_count {
+                    json["commit_count"] = serde_json::Value::Number(serde_json::Number::from(count));
+                }
+                if let Some(ref touched) = cap.user_last_touched_at {
+                    json["user_last_touched_at"] = serde_json::Value::String(touched.clone());
+                }
+
+                // Add declaration metadata if available
+                if let Some(ref qualified_name) = cap.qualified_name {
+                    json["qualified_name"] = serde_json::Value::String(qualified_name.clone());
+                }
+                if let Some(ref signature) = cap.signature {
+                    json["signature"] = serde_json::Value::String(signature.clone());
+                }
+                if let Some(ref doc) = cap.doc_comment {
+                    json["doc_comment"] = serde_json::Value::String(doc.clone());
+                }
+                if let Some(ref visibility) = cap.visibility {
+                    json["visibility"] = serde_json::Value::String(visibility.clone());
+                }
+                if let Some(end_line) = cap.end_line {
+                    json["line"] = serde_json::Value::from(cap.line);
+                    json["end_line"] = serde_json::Value::from(end_line);
+                }
+                if let Some(cell) = cap.cell {
+                    json["cell"] = serde_json::Value::from(cell);
+                }
+                if let (Some(ref package), Some(ref root)) = (&cap.package_name, &cap.package_root) {
+                    json["package"] = serde_json::Value::String(package.clone());
+                    json["package_root"] = serde_json::Value::String(root.clone());
+                }
+
+                // Add route fields for API endpoints
+                if let Some(ref method) = cap.http_method {
+                    json["http_method"] = serde_json::Value::String(method.clone());
+                }
+                if let Some(ref route) = cap.route_path {
+                    json["route_path"] = serde_json::Value::String(route.clone());
+                }
+
+                // Tests exercising the capability
+                if !cap.tests.is_empty() {
+                    json["tests"] = serde_json::json!(cap.tests);
+                }
+
+                // Dependency closure size and resolved callees
+                if let Some(weight) = cap.weight {
+                    json["weight"] = serde_json::Value::from(weight);
+                    json["calls"] = serde_json::json!(cap.calls);
+                }
+
+                // Size and complexity metrics
+                if let Some(ref metrics) = cap.metrics {
+                    json["metrics"] = serde_json::json!(metrics);
+                }
                 
                 json
             }).collect();
@@ -865,6 +1158,19 @@ fn print_echeo_banner() {
     "#.cyan());
 }
 
+/// Whether a file can yield capabilities (other files are only counted)
+fn is_scannable(path: &Path) -> bool {
+    manifest::is_manifest(path)
+        || contracts::is_contract(path)
+        || readme::is_readme(path)
+        || infra::is_infrastructure(path)
+        || path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|ext| HIGH_VALUE_EXTENSIONS.contains(&ext))
+            .unwrap_or(false)
+}
+
 fn print_detection(path: &Path, ext: &str) {
     // Sonar contact visualization
     let filename = path.file_name().unwrap_or_default().to_string_lossy();
@@ -873,10 +1179,21 @@ fn print_detection(path: &Path, ext: &str) {
     // Sonar color coding
     let tag = match ext {
         "rs" => "[RUST]".red().bold(),
-        "ts" | "tsx" => "[TYPESCRIPT]".blue().bold(),
+        "ts" | "tsx" | "mts" | "cts" => "[TYPESCRIPT]".blue().bold(),
+        "js" | "jsx" | "mjs" | "cjs" => "[JAVASCRIPT]".bright_yellow().bold(),
+        "vue" | "svelte" => "[COMPONENT]".bright_green().bold(),
         "sol" => "[SOLIDITY]".yellow().bold(),
         "go" => "[GO]".cyan().bold(),
         "py" => "[PYTHON]".green().bold(),
+        "rb" => "[RUBY]".bright_red().bold(),
+        "java" => "[JAVA]".bright_yellow().bold(),
+        "kt" => "[KOTLIN]".magenta().bold(),
+        "cs" => "[CSHARP]".bright_magenta().bold(),
+        "ipynb" => "[NOTEBOOK]".bright_green().bold(),
+        "manifest" => "[MANIFEST]".bright_white().bold(),
+        "contract" => "[CONTRACT]".bright_blue().bold(),
+        "infra" => "[INFRA]".bright_cyan().bold(),
+        "readme" => "[README]".white().bold(),
         _ => "[SIGNAL]".white(),
     };
 
@@ -896,6 +1213,54 @@ fn find_git_repo(path: &Path) -> Result<PathBuf> {
     Ok(workdir.to_path_buf())
 }
 
+/// Git repositories of the scanned files as (path prefix, repository
+/// directory) pairs, innermost first: the repository `root_path` is in, plus
+/// any nested ones (their `.git` is among the walked `entries`)
+fn git_repositories(root_path: &Path, entries: &[PathBuf]) -> Vec<(PathBuf, PathBuf)> {
+    let mut repos: Vec<(PathBuf, PathBuf)> = entries
+        .iter()
+        .filter(|entry| entry.file_name().map(|n| n == ".git").unwrap_or(false))
+        .filter_map(|entry| entry.parent())
+        .map(|dir| (dir.to_path_buf(), dir.to_path_buf()))
+        .collect();
+    if let Ok(repo_path) = find_git_repo(root_path) {
+        repos.push((root_path.to_path_buf(), repo_path));
+    }
+    repos.sort_by(|a, b| {
+        b.0.components()
+            .count()
+            .cmp(&a.0.components().count())
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    repos.dedup_by(|a, b| a.0 == b.0);
+    repos
+}
+
+/// Custom query directory: `--query-dir`, else `.echeo/queries` if present
+fn query_dir(query_dir: Option<&str>, root_path: &Path) -> Option<PathBuf> {
+    query_dir
+        .map(PathBuf::from)
+        .or_else(|| Some(root_path.join(".echeo").join("queries")).filter(|d| d.is_dir()))
+}
+
+/// The user's identities: `--identity` values, `echeo.identity` git config
+/// entries and user.name/user.email
+fn user_identities(extra: &[String]) -> Vec<Identity> {
+    let mut identities: Vec<Identity> = extra.iter().map(|text| Identity::parse(text)).collect();
+    if let Ok(config) = Config::open_default() {
+        if let Ok(entries) = config.multivar("echeo.identity", None) {
+            let _ = entries.for_each(|entry| {
+                identities.extend(entry.value().map(Identity::parse));
+            });
+        }
+    }
+    identities.push(Identity {
+        name: get_git_config("user.name").ok(),
+        email: get_git_config("user.email").ok(),
+    });
+    identities
+}
+
 fn get_git_config(key: &str) -> Result<String> {
     let config = Config::open_default()?;
     Ok(config.get_string(key)?)
@@ -0,0 +1,455 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::extractors::capability_from_span;
+use crate::shredder::{Capability, CapabilityKind};
+
+/// Package manifests understood by the analyzer: (file name, ecosystem,
+/// source extension the scan reports its capabilities under)
+const MANIFESTS: &[(&str, &str, &str)] = &[
+    ("Cargo.toml", "rust", "rs"),
+    ("package.json", "javascript", "js"),
+    ("go.mod", "go", "go"),
+    ("pyproject.toml", "python", "py"),
+    ("requirements.txt", "python", "py"),
+    ("Gemfile", "ruby", "rb"),
+];
+
+/// Lockfiles that pin the versions of a sibling manifest's packages
+const LOCKFILES: &[&str] = &["Cargo.lock", "poetry.lock", "package-lock.json"];
+
+/// Packages worth reporting: (language, package name or `prefix*`, capability)
+const SIGNALS: &[(&str, &str, &str)] = &[
+    // Rust
+    ("rust", "tokio", "Tokio async runtime"),
+    ("rust", "axum", "Axum web server"),
+    ("rust", "actix-web", "Actix web server"),
+    ("rust", "rocket", "Rocket web server"),
+    ("rust", "sqlx", "SQLx database access"),
+    ("rust", "diesel", "Diesel ORM"),
+    ("rust", "anchor-lang", "Solana Anchor program"),
+    ("rust", "solana-program", "Solana on-chain program"),
+    ("rust", "solana-sdk", "Solana client"),
+    ("rust", "ethers", "Ethereum client"),
+    ("rust", "alloy", "Ethereum client"),
+    ("rust", "wasm-bindgen", "WebAssembly bindings"),
+    ("rust", "tauri", "Tauri desktop app"),
+    ("rust", "bevy", "Bevy game engine"),
+    ("rust", "clap", "Command-line interface (clap)"),
+    ("rust", "tree-sitter", "Tree-sitter parsing"),
+    ("rust", "tonic", "gRPC services"),
+    ("rust", "async-stripe", "Uses Stripe SDK"),
+    // JavaScript / TypeScript
+    ("javascript", "stripe", "Uses Stripe SDK"),
+    ("javascript", "@stripe/*", "Uses Stripe SDK"),
+    ("javascript", "@solana/web3.js", "Solana web3.js client"),
+    ("javascript", "@coral-xyz/anchor", "Solana Anchor client"),
+    ("javascript", "ethers", "Ethereum client"),
+    ("javascript", "viem", "Ethereum client"),
+    ("javascript", "web3", "Ethereum client"),
+    ("javascript", "wagmi", "Ethereum wallet integration"),
+    ("javascript", "react-native", "React Native app"),
+    ("javascript", "expo", "React Native app"),
+    ("javascript", "next", "Next.js app"),
+    ("javascript", "react", "React UI"),
+    ("javascript", "vue", "Vue UI"),
+    ("javascript", "svelte", "Svelte UI"),
+    ("javascript", "@angular/core", "Angular app"),
+    ("javascript", "express", "Express web server"),
+    ("javascript", "fastify", "Fastify web server"),
+    ("javascript", "@nestjs/core", "NestJS backend"),
+    ("javascript", "@prisma/client", "Prisma ORM"),
+    ("javascript", "graphql", "GraphQL API"),
+    ("javascript", "socket.io", "Realtime websockets"),
+    ("javascript", "electron", "Electron desktop app"),
+    ("javascript", "three", "3D graphics (three.js)"),
+    ("javascript", "openai", "OpenAI API integration"),
+    ("javascript", "@supabase/supabase-js", "Supabase backend"),
+    ("javascript", "firebase", "Firebase backend"),
+    // Go
+    ("go", "github.com/gin-gonic/gin", "Gin web server"),
+    ("go", "github.com/labstack/echo*", "Echo web server"),
+    ("go", "github.com/gofiber/fiber*", "Fiber web server"),
+    ("go", "github.com/spf13/cobra", "Command-line interface (cobra)"),
+    ("go", "gorm.io/gorm", "GORM database access"),
+    ("go", "google.golang.org/grpc", "gRPC services"),
+    ("go", "github.com/stripe/stripe-go*", "Uses Stripe SDK"),
+    ("go", "github.com/ethereum/go-ethereum", "Ethereum client"),
+    ("go", "k8s.io/client-go", "Kubernetes client"),
+    // Python
+    ("python", "django", "Django web app"),
+    ("python", "flask", "Flask web app"),
+    ("python", "fastapi", "FastAPI web server"),
+    ("python", "sqlalchemy", "SQLAlchemy ORM"),
+    ("python", "celery", "Celery task queue"),
+    ("python", "torch", "PyTorch machine learning"),
+    ("python", "tensorflow", "TensorFlow machine learning"),
+    ("python", "scikit-learn", "scikit-learn machine learning"),
+    ("python", "transformers", "Hugging Face transformers"),
+    ("python", "langchain", "LangChain LLM apps"),
+    ("python", "openai", "OpenAI API integration"),
+    ("python", "stripe", "Uses Stripe SDK"),
+    ("python", "web3", "Ethereum client"),
+    ("python", "pandas", "Data analysis (pandas)"),
+    // Ruby
+    ("ruby", "rails", "Ruby on Rails app"),
+    ("ruby", "sidekiq", "Sidekiq background jobs"),
+    ("ruby", "stripe", "Uses Stripe SDK"),
+    ("ruby", "graphql", "GraphQL API"),
+];
+
+/// A package a manifest declares
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Whether a file is a package manifest the analyzer understands
+pub fn is_manifest(path: &Path) -> bool {
+    manifest_entry(path).is_some()
+}
+
+/// Source extension a manifest's capabilities are reported under
+/// (`Cargo.toml` -> "rs")
+pub fn manifest_extension(path: &Path) -> Option<&'static str> {
+    manifest_entry(path).map(|(_, _, ext)| *ext)
+}
+
+fn manifest_entry(path: &Path) -> Option<&'static (&'static str, &'static str, &'static str)> {
+    let file_name = path.file_name()?.to_str()?;
+    MANIFESTS.iter().find(|(name, _, _)| *name == file_name)
+}
+
+/// THE MANIFEST: Dependency capabilities of a package manifest ("Tokio async
+/// runtime", "Uses Stripe SDK"), one per recognized package. Versions come
+/// from the sibling lockfile when there is one: `read_lockfile` gives its
+/// content by name (`Cargo.lock`).
+pub fn analyze_source(
+    path: &Path,
+    source: &str,
+    read_lockfile: &dyn Fn(&str) -> Option<String>,
+) -> Result<Vec<Capability>> {
+    let language = match manifest_entry(path) {
+        Some((_, ecosystem, _)) => *ecosystem,
+        None => return Ok(Vec::new()),
+    };
+    let locked = lock_versions(read_lockfile);
+
+    let mut capabilities: Vec<Capability> = Vec::new();
+    for dependency in dependencies(path, source)? {
+        let label = match signal(language, &dependency.name) {
+            Some(label) => label,
+            None => continue,
+        };
+        if capabilities.iter().any(|c| c.name == label) {
+            continue; // `react` + `react-dom`, `stripe` + `@stripe/stripe-js`
+        }
+        let version = locked.get(&dependency.name).cloned().or(dependency.version);
+
+        let (start, end) = declaration_span(source, &dependency.name);
+        let mut cap = capability_from_span(label.to_string(), CapabilityKind::Dependency, source, start, end);
+        cap.qualified_name = dependency.name.clone();
+        cap.doc_comment = Some(match version {
+            Some(version) => format!("Depends on {} {}", dependency.name, version),
+            None => format!("Depends on {}", dependency.name),
+        });
+        capabilities.push(cap);
+    }
+    Ok(capabilities)
+}
+
+/// Packages declared by a manifest (runtime dependencies only)
+pub fn dependencies(path: &Path, source: &str) -> Result<Vec<Dependency>> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let dependencies = match file_name {
+        "Cargo.toml" => {
+            let manifest: toml::Table = toml::from_str(source)?;
+            let mut tables: Vec<&toml::Table> = Vec::new();
+            tables.extend(manifest.get("dependencies").and_then(|d| d.as_table()));
+            tables.extend(
+                manifest
+                    .get("workspace")
+                    .and_then(|w| w.get("dependencies"))
+                    .and_then(|d| d.as_table()),
+            );
+            // [target.'cfg(..)'.dependencies]
+            if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+                tables.extend(targets.values().filter_map(|t| t.get("dependencies")?.as_table()));
+            }
+            tables
+                .into_iter()
+                .flat_map(|table| table.iter())
+                .map(|(key, spec)| Dependency {
+                    // `foo = { package = "bar" }` renames bar
+                    name: spec
+                        .get("package")
+                        .and_then(|p| p.as_str())
+                        .unwrap_or(key)
+                        .to_string(),
+                    version: spec
+                        .as_str()
+                        .or_else(|| spec.get("version").and_then(|v| v.as_str()))
+                        .map(str::to_string),
+                })
+                .collect()
+        }
+        "package.json" => {
+            let manifest: serde_json::Value = serde_json::from_str(source)?;
+            ["dependencies", "peerDependencies"]
+                .iter()
+                .filter_map(|key| manifest.get(key).and_then(|d| d.as_object()))
+                .flat_map(|deps| deps.iter())
+                .map(|(name, version)| Dependency {
+                    name: name.clone(),
+                    version: version.as_str().map(str::to_string),
+                })
+                .collect()
+        }
+        "go.mod" => {
+            let mut in_block = false;
+            source
+                .lines()
+                .filter_map(|line| {
+                    let line = line.split("//").next().unwrap_or("").trim();
+                    let spec = if in_block {
+                        if line == ")" {
+                            in_block = false;
+                            return None;
+                        }
+                        line
+                    } else if line == "require (" {
+                        in_block = true;
+                        return None;
+                    } else {
+                        line.strip_prefix("require ")?
+                    };
+                    let mut parts = spec.split_whitespace();
+                    Some(Dependency {
+                        name: parts.next()?.to_string(),
+                        version: parts.next().map(str::to_string),
+                    })
+                })
+                .collect()
+        }
+        "pyproject.toml" => {
+            let manifest: toml::Table = toml::from_str(source)?;
+            // PEP 621 `[project] dependencies = ["fastapi>=0.100"]`
+            let pep621 = manifest
+                .get("project")
+                .and_then(|p| p.get("dependencies"))
+                .and_then(|d| d.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|d| d.as_str())
+                .filter_map(requirement);
+            // Poetry `[tool.poetry.dependencies] fastapi = "^0.100"`
+            let poetry = manifest
+                .get("tool")
+                .and_then(|t| t.get("poetry"))
+                .and_then(|p| p.get("dependencies"))
+                .and_then(|d| d.as_table())
+                .into_iter()
+                .flatten()
+                .filter(|(name, _)| name.as_str() != "python")
+                .map(|(name, spec)| Dependency {
+                    name: name.to_lowercase(),
+                    version: spec
+                        .as_str()
+                        .or_else(|| spec.get("version").and_then(|v| v.as_str()))
+                        .map(str::to_string),
+                });
+            pep621.chain(poetry).collect()
+        }
+        "requirements.txt" => source
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or("").trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('-'))
+            .filter_map(requirement)
+            .collect(),
+        "Gemfile" => source
+            .lines()
+            .filter_map(|line| {
+                let rest = line.trim().strip_prefix("gem ")?;
+                let mut args = rest.split(',').map(|a| a.trim().trim_matches(|c| c == '"' || c == '\''));
+                Some(Dependency {
+                    name: args.next()?.to_string(),
+                    version: args.next().filter(|v| !v.contains(':')).map(str::to_string),
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(dependencies)
+}
+
+/// A PEP 508 requirement: `fastapi[all]>=0.100; python_version > "3.8"`
+fn requirement(spec: &str) -> Option<Dependency> {
+    let spec = spec.split(';').next().unwrap_or("").trim();
+    let name_end = spec
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(spec.len());
+    let name = &spec[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+    // Skip extras: `[all]`
+    let rest = spec[name_end..].trim_start();
+    let version = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map(|(_, v)| v).unwrap_or(""),
+        None => rest,
+    }
+    .trim();
+    Some(Dependency {
+        name: name.to_lowercase().replace('_', "-"),
+        version: (!version.is_empty()).then(|| version.to_string()),
+    })
+}
+
+/// Lockfiles next to a manifest that its capabilities depend on
+pub fn lockfiles(path: &Path) -> Vec<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    LOCKFILES
+        .iter()
+        .map(|lockfile| dir.join(lockfile))
+        .filter(|lockfile| lockfile.is_file())
+        .collect()
+}
+
+/// Capability name for a package, if it is a known signal
+fn signal(language: &str, package: &str) -> Option<&'static str> {
+    SIGNALS
+        .iter()
+        .filter(|(lang, _, _)| *lang == language)
+        .find(|(_, pattern, _)| match pattern.strip_suffix('*') {
+            Some(prefix) => package.starts_with(prefix),
+            None => package == *pattern,
+        })
+        .map(|(_, _, label)| *label)
+}
+
+/// Exact versions pinned by the lockfile next to a manifest
+fn lock_versions(read_lockfile: &dyn Fn(&str) -> Option<String>) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+
+    // Cargo.lock and poetry.lock: `[[package]] name = ".." version = ".."`
+    for lockfile in ["Cargo.lock", "poetry.lock"] {
+        let lock: Option<toml::Table> = read_lockfile(lockfile)
+            .and_then(|s| toml::from_str(&s).ok());
+        let packages = lock
+            .as_ref()
+            .and_then(|l| l.get("package"))
+            .and_then(|p| p.as_array())
+            .into_iter()
+            .flatten();
+        for package in packages {
+            if let (Some(name), Some(version)) = (
+                package.get("name").and_then(|n| n.as_str()),
+                package.get("version").and_then(|v| v.as_str()),
+            ) {
+                versions.entry(name.to_string()).or_insert_with(|| version.to_string());
+            }
+        }
+    }
+
+    // package-lock.json v2+: `packages["node_modules/<name>"].version`
+    let npm_lock: Option<serde_json::Value> = read_lockfile("package-lock.json")
+        .and_then(|s| serde_json::from_str(&s).ok());
+    if let Some(packages) = npm_lock.as_ref().and_then(|l| l.get("packages")).and_then(|p| p.as_object()) {
+        for (key, package) in packages {
+            let name = match key.strip_prefix("node_modules/") {
+                Some(name) if !name.contains("/node_modules/") => name,
+                _ => continue,
+            };
+            if let Some(version) = package.get("version").and_then(|v| v.as_str()) {
+                versions.insert(name.to_string(), version.to_string());
+            }
+        }
+    }
+
+    versions
+}
+
+/// Byte span of the line declaring a package (the whole file if not found)
+fn declaration_span(source: &str, name: &str) -> (usize, usize) {
+    let is_name_char = |c: char| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | '@');
+    let found = source.match_indices(name).find(|(pos, _)| {
+        let before = source[..*pos].chars().next_back();
+        let after = source[pos + name.len()..].chars().next();
+        !before.map(is_name_char).unwrap_or(false) && !after.map(is_name_char).unwrap_or(false)
+    });
+    match found {
+        Some((pos, _)) => {
+            let start = source[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let start = start + (source[start..].len() - source[start..].trim_start().len());
+            let end = source[pos..].find('\n').map(|i| pos + i).unwrap_or(source.len());
+            (start, end)
+        }
+        None => (0, source.trim_end().len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detects_signals_with_locked_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{
+  "name": "shop",
+  "dependencies": {
+    "react": "^18.2.0",
+    "react-native": "0.73.0",
+    "stripe": "^14.0.0",
+    "left-pad": "1.3.0"
+  },
+  "devDependencies": { "typescript": "^5.0.0" }
+}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("package-lock.json"),
+            r#"{ "packages": { "node_modules/stripe": { "version": "14.5.0" } } }"#,
+        )
+        .unwrap();
+
+        let manifest = dir.path().join("package.json");
+        let source = fs::read_to_string(&manifest).unwrap();
+        let read_lockfile = |lockfile: &str| fs::read_to_string(dir.path().join(lockfile)).ok();
+        let caps = analyze_source(&manifest, &source, &read_lockfile).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.line)).collect();
+        assert_eq!(
+            summary,
+            vec![("React UI", 4), ("React Native app", 5), ("Uses Stripe SDK", 6)]
+        );
+        assert_eq!(caps[2].kind, CapabilityKind::Dependency);
+        assert_eq!(caps[2].doc_comment.as_deref(), Some("Depends on stripe 14.5.0"));
+        assert_eq!(caps[2].code_snippet, "\"stripe\": \"^14.0.0\",");
+    }
+
+    #[test]
+    fn test_parses_manifest_formats() {
+        let cargo = "[package]\nname = \"x\"\n\n[dependencies]\ntokio = { version = \"1\", features = [\"full\"] }\nserde = \"1\"\n";
+        let names = |path: &str, source: &str| -> Vec<String> {
+            dependencies(Path::new(path), source)
+                .unwrap()
+                .into_iter()
+                .map(|d| d.name)
+                .collect()
+        };
+        assert_eq!(names("Cargo.toml", cargo), vec!["serde", "tokio"]);
+
+        let go_mod = "module x\n\ngo 1.21\n\nrequire (\n\tgithub.com/gin-gonic/gin v1.9.1\n\tgorm.io/gorm v1.25.0 // indirect\n)\n";
+        assert_eq!(names("go.mod", go_mod), vec!["github.com/gin-gonic/gin", "gorm.io/gorm"]);
+
+        let pyproject = "[project]\ndependencies = [\"FastAPI[all]>=0.100\", \"celery; python_version > '3.8'\"]\n";
+        assert_eq!(names("pyproject.toml", pyproject), vec!["fastapi", "celery"]);
+
+        assert_eq!(names("Gemfile", "source 'https://rubygems.org'\ngem 'rails', '~> 7.1'\n"), vec!["rails"]);
+    }
+}
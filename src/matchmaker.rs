@@ -1,5 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::path::Path;
 
 use crate::vectorizer::EmbeddedCapability;
@@ -90,9 +91,23 @@ impl Matchmaker {
             reasons.push(format!("Type match: {}", capability.kind));
         }
 
+        // Boost when the need names a package the author builds with
+        if capability.kind == "dependency" {
+            if let Some(package) = &capability.qualified_name {
+                if need_lower.contains(&package.to_lowercase()) {
+                    score += 0.1;
+                    reasons.push(format!("Depends on {}", package));
+                }
+            }
+        }
+
         // Cap at 1.0
         score = score.min(1.0);
 
+        if !capability.tests.is_empty() {
+            reasons.push(format!("Covered by {} test(s)", capability.tests.len()));
+        }
+
         // Add capability description if available
         if !capability.code_snippet.is_empty() {
             reasons.push(format!("Has existing: {}", capability.name));
@@ -101,6 +116,44 @@ impl Matchmaker {
         (score, reasons)
     }
 
+    /// Order matches by score weighted by `recency` as of `now` (seconds since
+    /// the epoch); equal scores prefer tested, substantial code over trivial
+    /// helpers
+    fn rank(now: i64) -> impl Fn(&Match, &Match) -> Ordering {
+        move |a, b| {
+            let ranked = |m: &Match| m.score * Self::recency(&m.capability, now);
+            let substance = |m: &Match| {
+                m.capability
+                    .metrics
+                    .as_ref()
+                    .map(|metrics| (metrics.has_tests, metrics.lines_of_code * metrics.cyclomatic_complexity))
+                    .unwrap_or_default()
+            };
+            ranked(b)
+                .partial_cmp(&ranked(a))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| substance(b).cmp(&substance(a)))
+        }
+    }
+
+    /// How fresh the expertise behind a capability is: 1.0 within a year of
+    /// the user's last touch (or, without one, the last commit to it), down
+    /// to 0.5 after five. Capabilities without history aren't discounted.
+    fn recency(capability: &EmbeddedCapability, now: i64) -> f32 {
+        let touched = capability
+            .user_last_touched_at
+            .as_deref()
+            .or(capability.last_commit_at.as_deref())
+            .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok());
+        match touched {
+            Some(touched) => {
+                let years = (now - touched.timestamp()) as f32 / (365.25 * 24.0 * 3600.0);
+                (1.0 - (years - 1.0).max(0.0) / 8.0).max(0.5)
+            }
+            None => 1.0,
+        }
+    }
+
     /// Match a single need against all capabilities
     pub fn match_need(&self, need: &Need) -> Vec<Match> {
         let mut matches = Vec::new();
@@ -129,7 +182,7 @@ impl Matchmaker {
         }
 
         // Sort by score (highest first)
-        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        matches.sort_by(Self::rank(chrono::Utc::now().timestamp()));
 
         matches
     }
@@ -144,7 +197,7 @@ impl Matchmaker {
         }
 
         // Sort all matches by score
-        all_matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        all_matches.sort_by(Self::rank(chrono::Utc::now().timestamp()));
 
         // Return top K
         all_matches.into_iter().take(top_k).collect()
@@ -158,7 +211,7 @@ impl Matchmaker {
         
         // Group by need and find best combinations
         // For now, just return top matches (can be enhanced later)
-        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        matches.sort_by(Self::rank(chrono::Utc::now().timestamp()));
         
         matches.into_iter().take(max_capabilities).collect()
     }
@@ -208,6 +261,64 @@ impl Matchmaker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::metrics::CapabilityMetrics;
+
+    #[test]
+    fn test_ties_prefer_substantial_capabilities() {
+        let capability = |name: &str, lines_of_code: usize, has_tests: bool| EmbeddedCapability {
+            name: name.to_string(),
+            metrics: Some(CapabilityMetrics {
+                lines_of_code,
+                cyclomatic_complexity: 1,
+                has_tests,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let need = Matchmaker::create_sample_need("1".into(), "Need".into(), String::new(), None, vec![]);
+        let mut matches: Vec<Match> = [
+            capability("helper", 3, false),
+            capability("engine", 120, false),
+            capability("tested", 40, true),
+        ]
+        .into_iter()
+        .map(|capability| Match {
+            need: need.clone(),
+            capability,
+            score: 0.8,
+            reasons: vec![],
+        })
+        .collect();
+
+        matches.sort_by(Matchmaker::rank(0));
+        let names: Vec<_> = matches.iter().map(|m| m.capability.name.as_str()).collect();
+        assert_eq!(names, vec!["tested", "engine", "helper"]);
+    }
+
+    #[test]
+    fn test_prefers_recently_touched_capabilities() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:00+00:00").unwrap().timestamp();
+        let need = Matchmaker::create_sample_need("1".into(), "Need".into(), String::new(), None, vec![]);
+        let candidate = |name: &str, score: f32, touched: Option<&str>| Match {
+            need: need.clone(),
+            capability: EmbeddedCapability {
+                name: name.to_string(),
+                user_last_touched_at: touched.map(str::to_string),
+                ..Default::default()
+            },
+            score,
+            reasons: vec![],
+        };
+        let mut matches = [
+            candidate("stale", 0.9, Some("2019-06-01T00:00:00+00:00")),
+            candidate("fresh", 0.8, Some("2025-11-01T00:00:00+00:00")),
+            candidate("unknown", 0.7, None),
+        ];
+
+        matches.sort_by(Matchmaker::rank(now));
+        let names: Vec<_> = matches.iter().map(|m| m.capability.name.as_str()).collect();
+        assert_eq!(names, vec!["fresh", "unknown", "stale"]);
+    }
 
     #[test]
     fn test_cosine_similarity() {
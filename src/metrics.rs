@@ -0,0 +1,226 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::shredder::CapabilityKind;
+
+/// THE METRICS: Size and complexity of a capability, so substantial, tested
+/// code can be preferred over trivial helpers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityMetrics {
+    pub lines_of_code: usize,         // Non-blank, non-comment lines
+    pub cyclomatic_complexity: usize, // 1 + decision points
+    pub parameter_count: usize,       // Excluding self/this receivers
+    pub nesting_depth: usize,         // Deepest block nesting inside the body
+    pub has_tests: bool,              // Set once tests are attached (see evidence.rs)
+}
+
+/// Measure a capability from its source text. `ext` picks the comment
+/// syntax and language-specific decision points.
+pub fn measure(ext: &str, body: &str, signature: &str, name: &str, kind: CapabilityKind) -> CapabilityMetrics {
+    let hash_comments = matches!(ext, "py" | "rb");
+    let code = strip_comments_and_strings(body, hash_comments);
+
+    let lines_of_code = code.lines().filter(|l| !l.trim().is_empty()).count();
+
+    static DECISION_REGEX: OnceLock<Regex> = OnceLock::new();
+    let decision_regex = DECISION_REGEX.get_or_init(|| {
+        Regex::new(r"\b(?:if|elif|elsif|for|foreach|while|case|when|catch|except|rescue|unless|until|and|or)\b|&&|\|\|")
+            .expect("valid decision regex")
+    });
+    let mut decisions = decision_regex.find_iter(&code).count();
+    if ext == "rs" {
+        // Each `match` arm past the first is a branch, as `case`s are
+        static MATCH_REGEX: OnceLock<Regex> = OnceLock::new();
+        let match_regex = MATCH_REGEX.get_or_init(|| Regex::new(r"\bmatch\b").expect("valid match regex"));
+        let arms = code.matches("=>").count();
+        decisions += arms.saturating_sub(match_regex.find_iter(&code).count());
+    }
+
+    let parameter_count = match kind {
+        CapabilityKind::Class | CapabilityKind::Interface | CapabilityKind::Enum | CapabilityKind::Module => 0,
+        _ => parameter_count(signature, name),
+    };
+
+    CapabilityMetrics {
+        lines_of_code,
+        cyclomatic_complexity: 1 + decisions,
+        parameter_count,
+        nesting_depth: nesting_depth(&code, matches!(ext, "py" | "rb")),
+        has_tests: false,
+    }
+}
+
+/// Blank out comments and string contents (keeping line structure), so
+/// keywords in prose don't count as code
+fn strip_comments_and_strings(source: &str, hash_comments: bool) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let rest_starts = |s: &str| s.chars().enumerate().all(|(k, sc)| chars.get(i + k) == Some(&sc));
+
+        if (c == '/' && next == Some('/')) || (hash_comments && c == '#') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    out.push('\n');
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if rest_starts("\"\"\"") || rest_starts("'''") {
+            let quote = c;
+            out.push_str("\"\"");
+            i += 3;
+            while i < chars.len() && !(chars[i] == quote && chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote)) {
+                if chars[i] == '\n' {
+                    out.push('\n');
+                }
+                i += 1;
+            }
+            i += 3;
+        } else if c == '"' || c == '`' || c == '\'' {
+            // Strings end at their closing quote; an unclosed `'` (a Rust
+            // lifetime) is left alone
+            let close = (i + 1..chars.len())
+                .take_while(|&j| chars[j] != '\n' || c == '`')
+                .find(|&j| chars[j] == c && chars[j - 1] != '\\');
+            match close {
+                Some(end) => {
+                    out.push_str("\"\"");
+                    out.extend(chars[i + 1..end].iter().filter(|ch| **ch == '\n'));
+                    i = end + 1;
+                }
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Parameters in the list following the capability's name in its signature
+fn parameter_count(signature: &str, name: &str) -> usize {
+    let after_name = match signature.find(name) {
+        Some(pos) => &signature[pos + name.len()..],
+        None => return 0,
+    };
+    let open = match after_name.find('(') {
+        Some(open) => open,
+        None => return 0,
+    };
+
+    let mut depth = 0i32;
+    let mut params = Vec::new();
+    let mut current = String::new();
+    let mut previous = ' ';
+    for c in after_name[open + 1..].chars() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' if depth == 0 => break,
+            '>' if matches!(previous, '=' | '-') => {} // `=>`, `->` in defaults and types
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(std::mem::take(&mut current));
+                previous = c;
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+        previous = c;
+    }
+    params.push(current);
+
+    params
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .filter(|p| !matches!(*p, "self" | "&self" | "&mut self" | "mut self" | "cls" | "this"))
+        .filter(|p| !p.starts_with("this:")) // TypeScript `this` parameter
+        .count()
+}
+
+/// Deepest block nesting below the capability's own body: brace depth for
+/// C-family code, indentation levels for Python and Ruby (whose braces are
+/// dict and hash literals, not blocks)
+fn nesting_depth(code: &str, indented: bool) -> usize {
+    if !indented {
+        let mut depth = 0usize;
+        let mut max = 0usize;
+        for c in code.chars() {
+            match c {
+                '{' => {
+                    depth += 1;
+                    max = max.max(depth);
+                }
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        return max.saturating_sub(1);
+    }
+
+    let indents: Vec<usize> = code
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .collect();
+    let base = match indents.first() {
+        Some(base) => *base,
+        None => return 0,
+    };
+    let unit = indents.iter().filter(|i| **i > base).map(|i| i - base).min();
+    match unit {
+        Some(unit) => {
+            let max = indents.iter().max().copied().unwrap_or(base);
+            ((max - base) / unit).saturating_sub(1)
+        }
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measures_functions() {
+        let body = "pub fn route(&self, req: &Request, retries: u32) -> Result<()> {\n    // if this fails, retry\n    for _ in 0..retries {\n        match self.send(req) {\n            Ok(r) if r.ok() => return Ok(()),\n            _ => {}\n        }\n    }\n    Err(\"gave up if busy\".into())\n}\n";
+        let metrics = measure("rs", body, "pub fn route(&self, req: &Request, retries: u32) -> Result<()>", "route", CapabilityKind::Function);
+        assert_eq!(
+            metrics,
+            CapabilityMetrics {
+                lines_of_code: 9,
+                cyclomatic_complexity: 4, // for, match guard `if`, second arm
+                parameter_count: 2,
+                nesting_depth: 3,
+                has_tests: false,
+            }
+        );
+
+        let body = "def load(path, strict=False):\n    \"\"\"Load a file, or fail if missing.\"\"\"\n    if strict and not path:\n        raise ValueError(path)\n    return open(path)\n";
+        let metrics = measure("py", body, "def load(path, strict=False)", "load", CapabilityKind::Function);
+        assert_eq!(metrics.lines_of_code, 5);
+        assert_eq!(metrics.cyclomatic_complexity, 3);
+        assert_eq!(metrics.parameter_count, 2);
+        assert_eq!(metrics.nesting_depth, 1);
+
+        // Dict literals aren't blocks
+        let body = "def plan(items):\n    cfg = {\"a\": {\"b\": {\"c\": 1}}}\n    for item in items:\n        print(item)\n";
+        let metrics = measure("py", body, "def plan(items)", "plan", CapabilityKind::Function);
+        assert_eq!(metrics.nesting_depth, 1);
+    }
+}
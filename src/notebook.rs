@@ -0,0 +1,142 @@
+use anyhow::Result;
+use serde_json::Value;
+
+/// File extension of Jupyter notebooks
+pub const EXTENSION: &str = "ipynb";
+
+/// A code cell of a notebook
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotebookCell {
+    pub index: usize,        // Position in the notebook's `cells` (0-based, markdown cells included)
+    pub source: String,      // Cell code, with IPython magics commented out
+    pub line: Option<usize>, // Notebook file line of the cell's first source line, if known
+}
+
+/// THE NOTEBOOK: Python code cells of a Jupyter notebook, ready for the
+/// Python extractor. Notebooks whose kernel isn't Python have none.
+pub fn code_cells(source: &str) -> Result<Vec<NotebookCell>> {
+    let notebook: Value = serde_json::from_str(source)?;
+    let language = notebook.get("metadata").and_then(|m| {
+        m.pointer("/kernelspec/language")
+            .or_else(|| m.pointer("/language_info/name"))
+            .and_then(|l| l.as_str())
+    });
+    if language.map(|l| !l.eq_ignore_ascii_case("python")).unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+    let cells = match notebook.get("cells").and_then(|c| c.as_array()) {
+        Some(cells) => cells,
+        None => return Ok(Vec::new()),
+    };
+
+    // Every cell has exactly one `source` key, so the n-th one belongs to cell n
+    let source_lines = source_lines(source);
+    let source_lines = (source_lines.len() == cells.len()).then_some(source_lines);
+
+    Ok(cells
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| cell.get("cell_type").and_then(|t| t.as_str()) == Some("code"))
+        .filter_map(|(index, cell)| {
+            let code = match cell.get("source")? {
+                Value::String(code) => code.clone(),
+                Value::Array(lines) => lines.iter().filter_map(|l| l.as_str()).collect(),
+                _ => return None,
+            };
+            Some(NotebookCell {
+                index,
+                source: comment_magics(&code),
+                line: source_lines.as_ref().and_then(|lines| lines[index]),
+            })
+        })
+        .collect())
+}
+
+/// For each `"source":` key in the notebook file, the line its first source
+/// line is on (the next line when the list opens with one string per line)
+fn source_lines(notebook: &str) -> Vec<Option<usize>> {
+    let lines: Vec<&str> = notebook.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let value = line.trim_start().strip_prefix("\"source\":")?.trim();
+            Some(match value {
+                "[" => (i + 1 < lines.len()).then_some(i + 2),
+                "[]" | "[]," | "\"\"" | "\"\"," => None,
+                _ => Some(i + 1),
+            })
+        })
+        .collect()
+}
+
+/// Turn IPython magics (`%matplotlib inline`, `!pip install x`) into comments
+/// so the cell parses as plain Python
+fn comment_magics(code: &str) -> String {
+    code.split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('%') || trimmed.starts_with('!') {
+                format!("#{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shredder::Shredder;
+    use std::path::Path;
+
+    #[test]
+    fn test_extracts_code_cells() {
+        let notebook = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": [
+    "# Churn model"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "metadata": {},
+   "outputs": [],
+   "source": [
+    "%matplotlib inline\n",
+    "import pandas as pd\n",
+    "\n",
+    "def load_customers(path):\n",
+    "    return pd.read_csv(path)\n"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "metadata": {},
+   "outputs": [],
+   "source": "class ChurnModel:\n    pass\n"
+  }
+ ],
+ "metadata": {
+  "kernelspec": { "language": "python", "name": "python3" }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"##;
+        let cells = code_cells(notebook).unwrap();
+        assert_eq!(cells.len(), 2);
+        assert_eq!((cells[0].index, cells[0].line), (1, Some(15)));
+        assert!(cells[0].source.starts_with("#%matplotlib inline\nimport pandas"));
+        assert_eq!((cells[1].index, cells[1].line), (2, Some(26)));
+
+        let mut shredder = Shredder::new().unwrap();
+        let caps = shredder.shred_source(Path::new("analysis.ipynb"), notebook).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.cell, c.line)).collect();
+        assert_eq!(summary, vec![("load_customers", Some(1), 4), ("ChurnModel", Some(2), 1)]);
+        assert_eq!(caps[0].metrics.parameter_count, 1);
+    }
+}
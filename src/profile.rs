@@ -0,0 +1,242 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::extractors::ExtractorRegistry;
+use crate::vectorizer::EmbeddedCapability;
+
+/// Kinds that aren't code in a language: dependencies, infrastructure and
+/// README sections. They count toward every group but `languages`.
+const NON_CODE_KINDS: &[&str] = &["dependency", "infrastructure", "about"];
+
+/// The user's footprint in one repository, language, kind or package
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Footprint {
+    pub name: String,
+    pub capabilities: usize,  // Capabilities in the group
+    pub authored: usize,      // Capabilities the user wrote any of
+    pub self_authored: usize, // Capabilities the user wrote most of (>= 80%)
+    pub lines: f64,
+    pub authored_lines: f64, // Lines credited to the user
+    pub contribution: f64,   // Share of the group's lines, 0.0 to 100.0
+    pub share: f64,          // Share of the user's lines in its list, 0.0 to 1.0
+}
+
+/// A developer's footprint across a scanned tree
+#[derive(Debug, Default, Serialize)]
+pub struct ContributorProfile {
+    pub capabilities: usize,
+    pub authored: usize,
+    pub self_authored: usize,
+    pub authored_lines: f64,
+    pub repositories: Vec<Footprint>, // Each list: most authored lines first
+    pub languages: Vec<Footprint>,    // Code only, see `NON_CODE_KINDS`
+    pub kinds: Vec<Footprint>,
+    pub packages: Vec<Footprint>,
+}
+
+impl ContributorProfile {
+    /// THE PROFILE: Roll blamed capabilities up into how much of each
+    /// repository, language, kind and package the user wrote.
+    ///
+    /// A capability's lines are credited by its `contribution_percentage`;
+    /// capabilities without blame (untracked files, manifests, contracts) count
+    /// toward the totals but not the user's. Lines of a capability nested in
+    /// another (a method in its class) count once, for the innermost one.
+    /// `repositories` are the (path prefix, repository directory) pairs of the
+    /// scan, innermost first.
+    pub fn build(capabilities: &[EmbeddedCapability], repositories: &[(PathBuf, PathBuf)]) -> Self {
+        let registry = ExtractorRegistry::default();
+        let mut profile = Self::default();
+        let mut groups: [HashMap<String, Footprint>; 4] = Default::default();
+
+        for (cap, lines) in capabilities.iter().zip(own_lines(capabilities)) {
+            let lines = lines as f64;
+            let contribution = cap.contribution_percentage.unwrap_or(0.0);
+            let authored_lines = lines * contribution / 100.0;
+            let authored = authored_lines > 0.0;
+            let self_authored = contribution >= 80.0;
+
+            profile.capabilities += 1;
+            profile.authored += authored as usize;
+            profile.self_authored += self_authored as usize;
+            profile.authored_lines += authored_lines;
+
+            let repository = repositories
+                .iter()
+                .find(|(prefix, _)| Path::new(&cap.path).starts_with(prefix))
+                .map(|(_, repo_path)| repo_path.components().collect::<PathBuf>().display().to_string());
+            let language = registry
+                .for_extension(&cap.language)
+                .map(|extractor| extractor.name().to_string())
+                .unwrap_or_else(|| cap.language.clone());
+            let language = Some(language).filter(|_| !NON_CODE_KINDS.contains(&cap.kind.as_str()));
+            let names = [repository, language, Some(cap.kind.clone()), cap.package_name.clone()];
+            for (group, name) in groups.iter_mut().zip(names) {
+                let Some(name) = name else { continue };
+                let footprint = group.entry(name.clone()).or_insert_with(|| Footprint {
+                    name,
+                    ..Default::default()
+                });
+                footprint.capabilities += 1;
+                footprint.authored += authored as usize;
+                footprint.self_authored += self_authored as usize;
+                footprint.lines += lines;
+                footprint.authored_lines += authored_lines;
+            }
+        }
+
+        let [repositories, languages, kinds, packages] = groups.map(|group| {
+            let mut footprints: Vec<Footprint> = group.into_values().collect();
+            let authored_lines: f64 = footprints.iter().map(|footprint| footprint.authored_lines).sum();
+            for footprint in &mut footprints {
+                if footprint.lines > 0.0 {
+                    footprint.contribution = footprint.authored_lines / footprint.lines * 100.0;
+                }
+                if authored_lines > 0.0 {
+                    footprint.share = footprint.authored_lines / authored_lines;
+                }
+            }
+            footprints.sort_by(|a, b| {
+                b.authored_lines
+                    .total_cmp(&a.authored_lines)
+                    .then_with(|| b.lines.total_cmp(&a.lines))
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            footprints
+        });
+        profile.repositories = repositories;
+        profile.languages = languages;
+        profile.kinds = kinds;
+        profile.packages = packages;
+        profile
+    }
+
+    /// Share of the user's lines of code per language, for the loadout.
+    /// Without any blamed code (a scan outside git), the share of all code.
+    pub fn stack_dominance(&self) -> BTreeMap<String, f64> {
+        let total_lines: f64 = self.languages.iter().map(|language| language.lines).sum();
+        let authored = self.languages.iter().any(|language| language.authored_lines > 0.0);
+        self.languages
+            .iter()
+            .filter_map(|language| match authored {
+                true => Some((language.name.clone(), language.share)).filter(|(_, share)| *share > 0.0),
+                false if total_lines > 0.0 => Some((language.name.clone(), language.lines / total_lines)),
+                false => None,
+            })
+            .collect()
+    }
+}
+
+/// Lines each capability spans outside the capabilities nested in it, so a
+/// file's lines are counted once. Without an end line, a capability is one
+/// line.
+fn own_lines(capabilities: &[EmbeddedCapability]) -> Vec<usize> {
+    let span = |cap: &EmbeddedCapability| (cap.line, cap.end_line.unwrap_or(cap.line).max(cap.line));
+    let mut own: Vec<usize> = capabilities.iter().map(|cap| span(cap).1 - span(cap).0 + 1).collect();
+
+    let mut by_file: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, cap) in capabilities.iter().enumerate() {
+        by_file.entry(cap.path.as_str()).or_default().push(i);
+    }
+    for mut indices in by_file.into_values() {
+        // Outer before inner: by start, then the longer first
+        indices.sort_by_key(|&i| (span(&capabilities[i]).0, std::cmp::Reverse(span(&capabilities[i]).1)));
+        let mut enclosing: Vec<usize> = Vec::new();
+        for i in indices {
+            let (start, end) = span(&capabilities[i]);
+            while enclosing.last().is_some_and(|&outer| span(&capabilities[outer]).1 < end) {
+                enclosing.pop();
+            }
+            if let Some(&outer) = enclosing.last() {
+                own[outer] = own[outer].saturating_sub(end - start + 1);
+            }
+            enclosing.push(i);
+        }
+    }
+    own
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capability(path: &str, language: &str, lines: (usize, usize), contribution: Option<f64>) -> EmbeddedCapability {
+        let kind = match language {
+            "md" => "about",
+            "docker" => "infrastructure",
+            "toml" => "dependency",
+            _ => "function",
+        };
+        EmbeddedCapability {
+            name: "cap".to_string(),
+            path: path.to_string(),
+            language: language.to_string(),
+            kind: kind.to_string(),
+            line: lines.0,
+            end_line: Some(lines.1),
+            contribution_percentage: contribution,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rolls_up_authored_lines() {
+        let repositories = vec![(PathBuf::from("work/api"), PathBuf::from("work/api/"))];
+        let capabilities = vec![
+            capability("work/api/src/lib.rs", "rs", (1, 10), Some(100.0)),
+            capability("work/api/src/db.rs", "rs", (1, 10), Some(50.0)),
+            capability("work/web/app.ts", "ts", (1, 5), Some(100.0)),
+            capability("work/web/util.ts", "ts", (1, 20), Some(0.0)),
+            capability("work/api/Cargo.toml", "toml", (3, 3), None),
+        ];
+        let profile = ContributorProfile::build(&capabilities, &repositories);
+
+        assert_eq!((profile.capabilities, profile.authored, profile.self_authored), (5, 3, 2));
+        assert_eq!(profile.authored_lines, 20.0);
+        assert_eq!(profile.repositories.len(), 1);
+        assert_eq!(profile.repositories[0].name, "work/api");
+        assert_eq!(profile.repositories[0].capabilities, 3);
+        assert_eq!(profile.repositories[0].authored_lines, 15.0);
+
+        let rust = &profile.languages[0];
+        assert_eq!((rust.name.as_str(), rust.contribution, rust.share), ("rust", 75.0, 0.75));
+        let typescript = &profile.languages[1];
+        assert_eq!((typescript.name.as_str(), typescript.contribution), ("typescript", 20.0));
+        assert_eq!(profile.languages.len(), 2); // The manifest isn't code
+        assert_eq!(profile.kinds.iter().find(|k| k.name == "dependency").unwrap().lines, 1.0);
+
+        let dominance = profile.stack_dominance();
+        assert_eq!(dominance.get("rust"), Some(&0.75));
+        assert_eq!(dominance.get("typescript"), Some(&0.25));
+        assert_eq!(dominance.get("toml"), None);
+
+        // Outside git, every line of code counts
+        let unblamed = vec![
+            capability("a.py", "py", (1, 3), None),
+            capability("b.rs", "rs", (1, 1), None),
+            capability("README.md", "md", (1, 40), None),
+            capability("Dockerfile", "docker", (1, 12), None),
+        ];
+        let dominance = ContributorProfile::build(&unblamed, &[]).stack_dominance();
+        assert_eq!(dominance.get("python"), Some(&0.75));
+        assert_eq!(dominance.len(), 2);
+    }
+
+    #[test]
+    fn test_counts_nested_lines_once() {
+        // A class (1-20) with two methods, one listed twice
+        let capabilities = vec![
+            capability("shop.py", "py", (1, 20), Some(50.0)),
+            capability("shop.py", "py", (2, 6), Some(100.0)),
+            capability("shop.py", "py", (2, 6), Some(100.0)),
+            capability("shop.py", "py", (8, 17), Some(0.0)),
+            capability("other.py", "py", (1, 4), Some(100.0)),
+        ];
+        let profile = ContributorProfile::build(&capabilities, &[]);
+
+        let python = &profile.languages[0];
+        assert_eq!(python.lines, 24.0);
+        assert_eq!(python.authored_lines, 2.5 + 5.0 + 4.0);
+    }
+}
@@ -0,0 +1,185 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::extractors::capability_from_span;
+use crate::shredder::{Capability, CapabilityKind};
+
+/// Sections about process rather than what the project does
+const SKIPPED_SECTIONS: &[&str] = &[
+    "license",
+    "licence",
+    "contributing",
+    "contributors",
+    "authors",
+    "changelog",
+    "contents",
+    "table of contents",
+    "acknowledgements",
+    "acknowledgments",
+    "code of conduct",
+    "security",
+    "support",
+];
+
+/// Longest description kept per section
+const MAX_DESCRIPTION: usize = 500;
+
+/// Whether a file is a Markdown README
+pub fn is_readme(path: &Path) -> bool {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(file_name.as_str(), "readme.md" | "readme.markdown")
+}
+
+/// THE README: Project-level "about" capabilities, one per top-level README
+/// section (the title's intro and each `##` section), described by the
+/// section's prose so matching has context the code doesn't spell out.
+pub fn analyze_source(path: &Path, source: &str) -> Vec<Capability> {
+    static HEADING_REGEX: OnceLock<Regex> = OnceLock::new();
+    let heading_regex =
+        HEADING_REGEX.get_or_init(|| Regex::new(r"^(#{1,2})[ \t]+(.+?)[ \t#]*$").expect("valid heading regex"));
+
+    // (start byte, level, heading text) of each `#`/`##` heading outside code fences
+    let mut headings: Vec<(usize, usize, String)> = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed.trim_start().starts_with("```") || trimmed.trim_start().starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence {
+            if let Some(caps) = heading_regex.captures(trimmed) {
+                headings.push((offset, caps[1].len(), plain_text(&caps[2])));
+            }
+        }
+        offset += line.len();
+    }
+
+    let project = headings
+        .iter()
+        .find(|(_, level, _)| *level == 1)
+        .map(|(_, _, title)| title.clone())
+        .or_else(|| {
+            let dir = path.parent()?.file_name()?.to_str()?;
+            Some(dir.to_string())
+        })
+        .unwrap_or_else(|| "README".to_string());
+
+    let mut capabilities = Vec::new();
+    for (i, (start, level, heading)) in headings.iter().enumerate() {
+        if SKIPPED_SECTIONS.contains(&heading.to_lowercase().as_str()) {
+            continue;
+        }
+        let next = headings[i + 1..]
+            .iter()
+            .find(|(_, next_level, _)| *next_level <= 2)
+            .map(|(s, _, _)| *s)
+            .unwrap_or(source.len());
+        let end = start + source[*start..next].trim_end().len();
+        let body_start = source[*start..end].find('\n').map(|n| start + n + 1).unwrap_or(end);
+
+        let description = prose(&source[body_start..end]);
+        if description.is_empty() {
+            continue;
+        }
+        let mut cap = capability_from_span(heading.clone(), CapabilityKind::About, source, *start, end);
+        cap.qualified_name = if *level == 1 {
+            heading.clone()
+        } else {
+            format!("{}.{}", project, heading)
+        };
+        cap.doc_comment = Some(description);
+        capabilities.push(cap);
+    }
+    capabilities
+}
+
+/// Readable prose of a section: code blocks, images, badges and HTML are
+/// dropped and inline Markdown is reduced to its text
+fn prose(markdown: &str) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || line.starts_with('<') || line.starts_with("![") || line.starts_with("[![") {
+            continue;
+        }
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join(" "));
+                current.clear();
+            }
+            continue;
+        }
+        let text = plain_text(line.trim_start_matches(['#', '>', '-', '*', '+', ' ']));
+        if !text.is_empty() {
+            current.push(text);
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join(" "));
+    }
+
+    let text = paragraphs.join("\n");
+    if text.len() > MAX_DESCRIPTION {
+        let cut = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .take_while(|i| *i <= MAX_DESCRIPTION)
+            .last()
+            .unwrap_or(0);
+        format!("{}...", text[..cut].trim_end())
+    } else {
+        text
+    }
+}
+
+/// Inline Markdown reduced to text: `[docs](url)` -> `docs`, emphasis and
+/// code markers removed
+fn plain_text(markdown: &str) -> String {
+    static LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+    let link_regex = LINK_REGEX.get_or_init(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").expect("valid link regex"));
+    let text = link_regex.replace_all(markdown, "$1");
+    text.chars()
+        .filter(|c| !matches!(c, '*' | '`'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builds_about_capabilities_from_sections() {
+        let readme = "# Ledgerly\n\n[![CI](https://ci/badge.svg)](https://ci)\n\nDouble-entry **bookkeeping** for small shops.\n\n## Features\n\n- Reconciles [Stripe](https://stripe.com) payouts\n- Exports `CSV` reports\n\n### Details\n\nRuns nightly.\n\n## Install\n\n```sh\n# not a heading\ncargo install ledgerly\n```\n\n## License\n\nMIT\n";
+        let caps = analyze_source(Path::new("ledgerly/README.md"), readme);
+        let summary: Vec<_> = caps
+            .iter()
+            .map(|c| (c.qualified_name.as_str(), c.line, c.doc_comment.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Ledgerly", 1, "Double-entry bookkeeping for small shops."),
+                (
+                    "Ledgerly.Features",
+                    7,
+                    "Reconciles Stripe payouts Exports CSV reports\nDetails\nRuns nightly."
+                ),
+            ]
+        );
+        assert_eq!(caps[0].kind, CapabilityKind::About);
+        assert!(is_readme(Path::new("docs/Readme.md")));
+    }
+}
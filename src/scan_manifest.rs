@@ -0,0 +1,245 @@
+use anyhow::Result;
+use git2::{ObjectType, Oid};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::authorship::Identity;
+use crate::manifest;
+use crate::shredder::Capability;
+
+/// A scanned file: its content hash and the capabilities extracted from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub hash: String,
+    #[serde(default)]
+    pub blame: Option<String>, // Blob committed at the path in HEAD, which its blame follows
+    pub extension: String, // What the capabilities are reported under ("rs", "docker", ...)
+    pub capabilities: Vec<Capability>,
+}
+
+/// THE SCAN MANIFEST: What the previous scan extracted from each file, keyed
+/// by content hash (and, for blamed files, the blob committed in HEAD), so
+/// unchanged files aren't shredded (or blamed) again.
+///
+/// Stored at `.echeo/scan_manifest.json`. A manifest written by another
+/// version of echeo, with different custom queries, or with different inputs
+/// to authorship (see `fingerprint`) is discarded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanManifest {
+    pub version: String,
+    pub fingerprint: String, // Hash of the custom queries and authorship inputs
+    pub files: BTreeMap<String, FileEntry>,
+}
+
+impl ScanManifest {
+    /// Load the manifest at `path`; empty if missing, unreadable or stale
+    pub fn load(path: &Path, fingerprint: &str) -> Self {
+        let manifest: Option<ScanManifest> = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        manifest
+            .filter(|m| m.version == env!("CARGO_PKG_VERSION") && m.fingerprint == fingerprint)
+            .unwrap_or_default()
+    }
+
+    /// The previous scan's entry for `path`, if neither the file nor its
+    /// committed blob (`blame`, see `Shredder::blame_key`) changed since
+    pub fn unchanged(&self, path: &Path, hash: &str, blame: Option<&str>) -> Option<&FileEntry> {
+        self.files
+            .get(&path.display().to_string())
+            .filter(|entry| entry.hash == hash && entry.blame.as_deref() == blame)
+    }
+
+    /// Manifest of a finished scan: every hashed file (with its blame key),
+    /// with the capabilities found in it. Files no longer present are thereby
+    /// dropped.
+    pub fn from_scan(
+        fingerprint: &str,
+        hashes: HashMap<PathBuf, (String, Option<String>)>,
+        capabilities: &[(PathBuf, String, Capability)],
+    ) -> Self {
+        let mut files: BTreeMap<String, FileEntry> = hashes
+            .into_iter()
+            .map(|(path, (hash, blame))| {
+                let entry = FileEntry {
+                    hash,
+                    blame,
+                    extension: String::new(),
+                    capabilities: Vec::new(),
+                };
+                (path.display().to_string(), entry)
+            })
+            .collect();
+        for (path, ext, cap) in capabilities {
+            if let Some(entry) = files.get_mut(&path.display().to_string()) {
+                entry.extension = ext.clone();
+                entry.capabilities.push(cap.clone());
+            }
+        }
+        ScanManifest {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            fingerprint: fingerprint.to_string(),
+            files,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Content hash of the files a scanned file's capabilities derive from: the
+/// file itself, plus the lockfiles next to a package manifest. This is git's
+/// blob id when there is no lockfile.
+pub fn file_hash(path: &Path) -> Option<String> {
+    let mut content = fs::read(path).ok()?;
+    if manifest::is_manifest(path) {
+        for lockfile in manifest::lockfiles(path) {
+            content.extend(fs::read(lockfile).unwrap_or_default());
+        }
+    }
+    content_hash(&content)
+}
+
+/// Git blob id of some content
+pub fn content_hash(content: &[u8]) -> Option<String> {
+    Oid::hash_object(ObjectType::Blob, content).ok().map(|oid| oid.to_string())
+}
+
+/// Fingerprint of everything besides file content that cached capabilities
+/// depend on: the custom queries in `query_dir` and, since capabilities carry
+/// their blame, each repository's `.mailmap` and ignored revisions and the
+/// user's identities. Empty when there are none of these. Which commits touch
+/// a file is keyed per file instead (`FileEntry::blame`), so a new commit
+/// doesn't invalidate every file.
+pub fn fingerprint(
+    query_dir: Option<&Path>,
+    repositories: &[(PathBuf, PathBuf)],
+    identities: &[Identity],
+) -> String {
+    let mut paths: Vec<PathBuf> = query_dir
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("scm"))
+        .collect();
+    if paths.is_empty() && repositories.is_empty() {
+        return String::new();
+    }
+    paths.sort();
+    let mut content = Vec::new();
+    for path in paths {
+        content.extend(path.display().to_string().into_bytes());
+        content.extend(fs::read(&path).unwrap_or_default());
+    }
+    for (_, repo_path) in repositories {
+        content.extend(repo_path.display().to_string().into_bytes());
+        for file in [".mailmap", ".git-blame-ignore-revs"] {
+            content.extend(fs::read(repo_path.join(file)).unwrap_or_default());
+        }
+    }
+    if !repositories.is_empty() {
+        content.extend(format!("{:?}", identities).into_bytes());
+    }
+    content_hash(&content).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shredder::Shredder;
+
+    #[test]
+    fn test_reuses_unchanged_files_and_drops_deleted_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("lib.py");
+        let old = dir.path().join("old.py");
+        fs::write(&lib, "def handler():\n    pass\n").unwrap();
+        fs::write(&old, "def legacy():\n    pass\n").unwrap();
+
+        let mut shredder = Shredder::new().unwrap();
+        let mut hashes = HashMap::new();
+        let mut caps = Vec::new();
+        for path in [&lib, &old] {
+            hashes.insert(path.clone(), (file_hash(path).unwrap(), None));
+            for cap in shredder.shred_file(path).unwrap() {
+                caps.push((path.clone(), "py".to_string(), cap));
+            }
+        }
+        let manifest_path = dir.path().join(".echeo").join("scan_manifest.json");
+        ScanManifest::from_scan("", hashes, &caps).save(&manifest_path).unwrap();
+
+        // Git's blob id for the same content
+        assert_eq!(
+            content_hash(b"hello\n").unwrap(),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+
+        let manifest = ScanManifest::load(&manifest_path, "");
+        let entry = manifest.unchanged(&lib, &file_hash(&lib).unwrap(), None).unwrap();
+        assert_eq!(entry.extension, "py");
+        assert_eq!(entry.capabilities[0].name, "handler");
+        assert_eq!(entry.capabilities[0].line, 1);
+
+        // Same content, but committed differently since: blame may differ
+        assert!(manifest.unchanged(&lib, &file_hash(&lib).unwrap(), Some("abc")).is_none());
+
+        fs::write(&lib, "def handler(event):\n    pass\n").unwrap();
+        assert!(manifest.unchanged(&lib, &file_hash(&lib).unwrap(), None).is_none());
+
+        // Another scan without old.py forgets it
+        let hashes = HashMap::from([(lib.clone(), (file_hash(&lib).unwrap(), None))]);
+        let rescanned = ScanManifest::from_scan("", hashes, &[]);
+        assert_eq!(rescanned.files.len(), 1);
+        assert!(rescanned.files[&lib.display().to_string()].capabilities.is_empty());
+
+        // Different custom queries invalidate the manifest
+        assert!(ScanManifest::load(&manifest_path, "other").files.is_empty());
+    }
+
+    #[test]
+    fn test_fingerprint_follows_authorship_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("Dev", "dev@example.com").unwrap();
+        let commit = |file: &str, content: &str| {
+            fs::write(dir.path().join(file), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(file)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, file, &tree, &parents).unwrap();
+        };
+        let repositories = vec![(dir.path().to_path_buf(), dir.path().to_path_buf())];
+        let me = vec![Identity::parse("Dev <dev@example.com>")];
+        let (a, b) = (dir.path().join("a.py"), dir.path().join("b.py"));
+
+        assert_eq!(fingerprint(None, &[], &me), "");
+        commit("a.py", "def a():\n    pass\n");
+        commit("b.py", "def b():\n    pass\n");
+        let first = fingerprint(None, &repositories, &me);
+        let shredder = Shredder::with_authorship(dir.path(), &me).unwrap();
+        let (a_key, b_key) = (shredder.blame_key(&a).unwrap(), shredder.blame_key(&b).unwrap());
+
+        // A new commit only changes the blame key of the files it touches
+        commit("b.py", "def b():\n    return 1\n");
+        assert_eq!(fingerprint(None, &repositories, &me), first);
+        let shredder = Shredder::with_authorship(dir.path(), &me).unwrap();
+        assert_eq!(shredder.blame_key(&a).unwrap(), a_key);
+        assert_ne!(shredder.blame_key(&b).unwrap(), b_key);
+        assert_eq!(Shredder::new().unwrap().blame_key(&a), None);
+
+        assert_ne!(fingerprint(None, &repositories, &[]), first);
+        fs::write(dir.path().join(".mailmap"), "Dev <dev@example.com> <old@example.com>\n").unwrap();
+        assert_ne!(fingerprint(None, &repositories, &me), first);
+    }
+}
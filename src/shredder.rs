@@ -1,67 +1,180 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use tree_sitter::Parser;
-use crate::authorship::{AuthorshipAnalyzer, AuthorshipInfo};
+use crate::authorship::{AuthorshipAnalyzer, AuthorshipInfo, Identity};
+use crate::evidence;
+use crate::extractors::{query, CustomQueries, ExtractorRegistry, LanguageExtractor, SourceFile};
+use crate::graph;
+use crate::metrics::{self, CapabilityMetrics};
+use crate::notebook;
 
 /// THE SHREDDER: Extracts capabilities from code using AST parsing
 pub struct Shredder {
     parser: Parser,
+    registry: ExtractorRegistry,
+    custom_queries: CustomQueries,
     authorship_analyzer: Option<AuthorshipAnalyzer>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Capability {
     pub name: String,
+    pub qualified_name: String, // Enclosing module/class path, e.g. "Matchmaker::match_need"
     pub kind: CapabilityKind,
+    pub visibility: Visibility,
     pub line: usize,
+    pub end_line: usize,
+    pub byte_range: (usize, usize), // Start and end byte offsets in the file
+    pub signature: String, // Declaration header without the body
+    pub doc_comment: Option<String>, // Leading doc comment or docstring, markers stripped
     pub code_snippet: String, // The actual code for embedding
     pub authorship: Option<AuthorshipInfo>, // Git blame authorship info
+    pub route: Option<HttpRoute>, // Set for ApiRoute capabilities when known
+    pub tests: Vec<TestEvidence>, // Tests exercising this capability (see evidence.rs)
+    pub calls: Vec<String>, // Names called in the body, resolved by graph.rs
+    #[serde(default)]
+    pub references: Vec<String>, // Identifiers in a test's full body (see evidence.rs)
+    pub metrics: CapabilityMetrics, // Size and complexity
+    pub cell: Option<usize>, // Notebook cell index; `line` is then within the cell (see notebook.rs)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CapabilityKind {
     Function,
     Class,
     ApiRoute,
-    Component, // React/Vue components
+    Component,  // React/Vue components
+    Interface,  // Rust traits, TS/Go/Java interfaces, Python protocols
+    Enum,
+    CliCommand, // clap/argparse/click/cobra/commander commands
+    Test,       // Attached to the capabilities they exercise, never reported alone
+    Module,     // Rust inline modules, TS namespaces, Ruby modules
+    Dependency, // Notable packages from manifests (see manifest.rs)
+    Schema,     // Tables, messages and GraphQL types from API contracts (see contracts.rs)
+    Infrastructure, // Dockerfiles, Terraform, Kubernetes and CI workflows (see infra.rs)
+    About,      // Project descriptions from README sections (see readme.rs)
+}
+
+impl CapabilityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CapabilityKind::Function => "function",
+            CapabilityKind::Class => "class",
+            CapabilityKind::Component => "component",
+            CapabilityKind::ApiRoute => "api_route",
+            CapabilityKind::Interface => "interface",
+            CapabilityKind::Enum => "enum",
+            CapabilityKind::CliCommand => "cli_command",
+            CapabilityKind::Test => "test",
+            CapabilityKind::Module => "module",
+            CapabilityKind::Dependency => "dependency",
+            CapabilityKind::Schema => "schema",
+            CapabilityKind::Infrastructure => "infrastructure",
+            CapabilityKind::About => "about",
+        }
+    }
+
+    /// Parse a kind from its `as_str` name (also used as query capture names)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "function" => Some(CapabilityKind::Function),
+            "class" => Some(CapabilityKind::Class),
+            "component" => Some(CapabilityKind::Component),
+            "api_route" => Some(CapabilityKind::ApiRoute),
+            "interface" => Some(CapabilityKind::Interface),
+            "enum" => Some(CapabilityKind::Enum),
+            "cli_command" => Some(CapabilityKind::CliCommand),
+            "test" => Some(CapabilityKind::Test),
+            "module" => Some(CapabilityKind::Module),
+            "dependency" => Some(CapabilityKind::Dependency),
+            "schema" => Some(CapabilityKind::Schema),
+            "infrastructure" => Some(CapabilityKind::Infrastructure),
+            "about" => Some(CapabilityKind::About),
+            _ => None,
+        }
+    }
+}
+
+/// Visibility of a capability in its language's terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Public,    // pub, export, public, exported Go identifiers
+    Internal,  // pub(crate), Java package-private
+    Protected,
+    Private,   // Unexported/underscored names, private members
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Internal => "internal",
+            Visibility::Protected => "protected",
+            Visibility::Private => "private",
+        }
+    }
+}
+
+/// A test that exercises a capability
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestEvidence {
+    pub name: String,
+    pub path: String,
+    pub line: usize,
+}
+
+/// HTTP method and path served by an API route
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpRoute {
+    pub method: String, // "GET", "POST", ... or "ANY"
+    pub path: String,   // As written in the framework, e.g. "/users/:id"
 }
 
 impl Shredder {
     pub fn new() -> Result<Self> {
         let parser = Parser::new();
-        Ok(Self { 
+        Ok(Self {
             parser,
+            registry: ExtractorRegistry::default(),
+            custom_queries: CustomQueries::default(),
             authorship_analyzer: None,
         })
     }
 
     /// Create a new Shredder with authorship tracking enabled
-    pub fn with_authorship(
-        repo_path: &Path,
-        user_email: Option<String>,
-        user_name: Option<String>,
-    ) -> Result<Self> {
+    pub fn with_authorship(repo_path: &Path, identities: &[Identity]) -> Result<Self> {
         let parser = Parser::new();
-        let authorship_analyzer = AuthorshipAnalyzer::new(repo_path, user_email, user_name).ok();
+        let authorship_analyzer = AuthorshipAnalyzer::new(repo_path, identities).ok();
         Ok(Self {
             parser,
+            registry: ExtractorRegistry::default(),
+            custom_queries: CustomQueries::default(),
             authorship_analyzer,
         })
     }
 
-    /// Extract code snippet from a node (limited to 500 chars for embedding)
-    fn extract_code_snippet(node: &tree_sitter::Node, source: &str) -> String {
-        let start_byte = node.start_byte();
-        let end_byte = node.end_byte();
-        let snippet = &source[start_byte..end_byte];
-        
-        // Limit to 500 chars, but try to keep it readable
-        if snippet.len() > 500 {
-            snippet.chars().take(500).collect::<String>() + "..."
-        } else {
-            snippet.to_string()
-        }
+    /// Extend the built-in extraction with user-supplied queries
+    pub fn with_queries(mut self, queries: CustomQueries) -> Self {
+        self.custom_queries = queries;
+        self
+    }
+
+    /// Register an additional language extractor (overrides built-ins for the same extension)
+    #[allow(dead_code)]
+    pub fn register_extractor(&mut self, extractor: Box<dyn LanguageExtractor>) {
+        self.registry.register(extractor);
+    }
+
+    /// What the blame of `path` depends on besides its content: the blob
+    /// committed at it in HEAD. `None` without authorship tracking.
+    pub fn blame_key(&self, path: &Path) -> Option<String> {
+        let analyzer = self.authorship_analyzer.as_ref()?;
+        analyzer.committed_blob(path).map(|oid| oid.to_string())
     }
 
     /// Shred a file and extract its capabilities
@@ -71,456 +184,359 @@ impl Shredder {
             .and_then(|e| e.to_str())
             .unwrap_or("");
 
-        let language = match ext {
-            "ts" | "tsx" => tree_sitter_typescript::language_typescript(),
-            "js" | "jsx" => tree_sitter_typescript::language_typescript(), // JS uses TS parser
-            "rs" => tree_sitter_rust::language(),
-            "py" => tree_sitter_python::language(),
-            "go" => tree_sitter_go::language(),
-            _ => return Ok(vec![]), // Unsupported language
-        };
-
-        self.parser.set_language(language)?;
+        if ext != notebook::EXTENSION && self.registry.for_extension(ext).is_none() {
+            return Ok(vec![]); // Unsupported language
+        }
 
         let source_code = fs::read_to_string(path)?;
-        let tree = self.parser.parse(&source_code, None).ok_or_else(|| {
-            anyhow::anyhow!("Failed to parse {}", path.display())
-        })?;
-
-        let root_node = tree.root_node();
-        let mut capabilities = Vec::new();
+        self.shred_source(path, &source_code)
+    }
 
-        // Extract based on language
-        match ext {
-            "ts" | "tsx" | "js" | "jsx" => {
-                capabilities.extend(self.extract_typescript_capabilities(&root_node, &source_code, path)?);
-            }
-            "rs" => {
-                capabilities.extend(self.extract_rust_capabilities(&root_node, &source_code, path)?);
-            }
-            "py" => {
-                capabilities.extend(self.extract_python_capabilities(&root_node, &source_code, path)?);
-            }
-            "go" => {
-                capabilities.extend(self.extract_go_capabilities(&root_node, &source_code, path)?);
-            }
-            _ => {}
+    /// Shred in-memory source code, using `path` to pick the language
+    pub fn shred_source(&mut self, path: &Path, source_code: &str) -> Result<Vec<Capability>> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        if ext == notebook::EXTENSION {
+            return self.shred_notebook(path, source_code);
         }
 
+        let mut capabilities = self.shred_as(path, ext, source_code)?;
+        self.attach_authorship(path, &mut capabilities, |cap| Some((cap.line, cap.end_line)));
         Ok(capabilities)
     }
 
-    /// Extract TypeScript/JavaScript capabilities
-    fn extract_typescript_capabilities(
-        &self,
-        root: &tree_sitter::Node,
-        source: &str,
-        path: &Path,
-    ) -> Result<Vec<Capability>> {
+    /// Shred each code cell of a Jupyter notebook as Python. Capability lines
+    /// are within their cell; blame uses the cell's lines in the notebook file.
+    fn shred_notebook(&mut self, path: &Path, source_code: &str) -> Result<Vec<Capability>> {
+        let cells = notebook::code_cells(source_code)?;
         let mut capabilities = Vec::new();
-        self.traverse_typescript_node(root, source, &mut capabilities, false, path)?;
+        for cell in &cells {
+            for mut cap in self.shred_as(path, "py", &cell.source)? {
+                cap.cell = Some(cell.index);
+                capabilities.push(cap);
+            }
+        }
+        let first_lines: HashMap<usize, usize> = cells
+            .iter()
+            .filter_map(|cell| Some((cell.index, cell.line?)))
+            .collect();
+        self.attach_authorship(path, &mut capabilities, |cap| {
+            let first = first_lines.get(&cap.cell?)?;
+            Some((first + cap.line - 1, first + cap.end_line - 1))
+        });
         Ok(capabilities)
     }
 
-    fn traverse_typescript_node(
-        &self,
-        node: &tree_sitter::Node,
-        source: &str,
-        capabilities: &mut Vec<Capability>,
-        is_exported: bool,
-        path: &Path,
-    ) -> Result<()> {
-        let node_type = node.kind();
-        let mut current_is_exported = is_exported;
-
-        // Check for export keyword
-        if node_type == "export_statement" || node_type == "export" {
-            current_is_exported = true;
-        }
+    /// Extract capabilities from source written in the language of `ext`
+    /// (without authorship)
+    fn shred_as(&mut self, path: &Path, ext: &str, source_code: &str) -> Result<Vec<Capability>> {
+        let extractor = match self.registry.for_extension(ext) {
+            Some(extractor) => extractor,
+            None => return Ok(vec![]), // Unsupported language
+        };
 
-        match node_type {
-            "function_declaration" => {
-                // Look for function name
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        if !name.is_empty() {
-                            // Check if it's a React component (PascalCase)
-                            let is_component = name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false);
-                            
-                            let code_snippet = Self::extract_code_snippet(node, source);
-                            let line = node.start_position().row + 1;
-                            let authorship = self.get_authorship(path, line, line);
-                            
-                            capabilities.push(Capability {
-                                name: name.clone(),
-                                kind: if is_component {
-                                    CapabilityKind::Component
-                                } else {
-                                    CapabilityKind::Function
-                                },
-                                line,
-                                code_snippet,
-                                authorship,
-                            });
-                        }
-                        break;
-                    }
-                }
-            }
-            "class_declaration" => {
-                // Look for class name
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "type_identifier" || child.kind() == "identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        if !name.is_empty() {
-                            let code_snippet = Self::extract_code_snippet(node, source);
-                            let line = node.start_position().row + 1;
-                            let authorship = self.get_authorship(path, line, line);
-                            capabilities.push(Capability {
-                                name,
-                                kind: CapabilityKind::Class,
-                                line,
-                                code_snippet,
-                                authorship,
-                            });
-                        }
-                        break;
-                    }
-                }
+        let tree = match extractor.grammar() {
+            Some(language) => {
+                self.parser.set_language(language)?;
+                let tree = self.parser.parse(source_code, None).ok_or_else(|| {
+                    anyhow::anyhow!("Failed to parse {}", path.display())
+                })?;
+                Some(tree)
             }
-            "variable_declarator" => {
-                // Check for arrow functions or const exports
-                let mut name = None;
-                let mut has_function = false;
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "identifier" {
-                        name = Some(child.utf8_text(source.as_bytes())?.to_string());
-                    }
-                    if child.kind() == "arrow_function" || child.kind() == "function" {
-                        has_function = true;
-                    }
-                }
-                if has_function {
-                    if let Some(n) = name {
-                        let is_component = n.chars().next().map(|c| c.is_uppercase()).unwrap_or(false);
-                        let code_snippet = Self::extract_code_snippet(node, source);
-                        let line = node.start_position().row + 1;
-                        let authorship = self.get_authorship(path, line, line);
-                        
-                        capabilities.push(Capability {
-                            name: n,
-                            kind: if is_component {
-                                CapabilityKind::Component
-                            } else {
-                                CapabilityKind::Function
-                            },
-                            line,
-                            code_snippet,
-                            authorship,
-                        });
-                    }
-                }
-            }
-            "method_definition" => {
-                // Extract method names
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "property_identifier" || child.kind() == "identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        if !name.is_empty() && (name == "get" || name == "post" || name == "put" || name == "delete") {
-                            // This might be an API route
-                            let code_snippet = Self::extract_code_snippet(node, source);
-                            let line = node.start_position().row + 1;
-                            let authorship = self.get_authorship(path, line, line);
-                            
-                            capabilities.push(Capability {
-                                name,
-                                kind: CapabilityKind::ApiRoute,
-                                line,
-                                code_snippet,
-                                authorship,
-                            });
-                        }
-                        break;
+            None => None,
+        };
+
+        let file = SourceFile {
+            path,
+            source: source_code,
+            root: tree.as_ref().map(|t| t.root_node()),
+        };
+        let mut capabilities = extractor.extract(&file)?;
+
+        // Custom queries add captures the built-in extractor doesn't know about
+        if let Some(root) = file.root {
+            for custom in self.custom_queries.for_language(extractor.name()) {
+                for cap in query::capabilities(custom, root, source_code) {
+                    let duplicate = capabilities
+                        .iter()
+                        .any(|c| c.line == cap.line && c.name == cap.name && c.kind == cap.kind);
+                    if !duplicate {
+                        capabilities.push(cap);
                     }
                 }
             }
-            _ => {}
         }
 
-        // Recurse into children
-        for i in 0..node.child_count() {
-            if let Some(child) = node.child(i) {
-                self.traverse_typescript_node(&child, source, capabilities, current_is_exported, path)?;
+        for cap in &mut capabilities {
+            let (start, end) = cap.byte_range;
+            let body = source_code.get(start..end).unwrap_or("");
+            cap.calls = graph::called_names(body, &cap.name);
+            if cap.kind == CapabilityKind::Test {
+                cap.references = evidence::referenced_names(body);
             }
+            cap.metrics = metrics::measure(ext, body, &cap.signature, &cap.name, cap.kind);
         }
 
-        Ok(())
-    }
-
-    /// Extract Rust capabilities
-    fn extract_rust_capabilities(
-        &self,
-        root: &tree_sitter::Node,
-        source: &str,
-        path: &Path,
-    ) -> Result<Vec<Capability>> {
-        let mut capabilities = Vec::new();
-        self.traverse_rust_node(root, source, &mut capabilities, false, path)?;
         Ok(capabilities)
     }
 
-    fn traverse_rust_node(
+    /// Blame `path` once and attach authorship to each capability over the
+    /// file lines `file_lines` gives (none for capabilities it can't place)
+    fn attach_authorship(
         &self,
-        node: &tree_sitter::Node,
-        source: &str,
-        capabilities: &mut Vec<Capability>,
-        is_pub: bool,
         path: &Path,
-    ) -> Result<()> {
-        let node_type = node.kind();
-        let mut current_is_pub = is_pub;
-
-        // Check for pub keyword
-        if node_type == "visibility_modifier" {
-            current_is_pub = true;
+        capabilities: &mut [Capability],
+        file_lines: impl Fn(&Capability) -> Option<(usize, usize)>,
+    ) {
+        let analyzer = match &self.authorship_analyzer {
+            Some(analyzer) if !capabilities.is_empty() => analyzer,
+            _ => return,
+        };
+        let blame = match analyzer.blame(path) {
+            Ok(blame) => blame,
+            Err(_) => return, // Untracked file
+        };
+        for cap in capabilities.iter_mut() {
+            cap.authorship = file_lines(cap).and_then(|(start, end)| analyzer.analyze_blame(&blame, start, end).ok());
         }
+    }
+}
 
-        match node_type {
-            "function_item" => {
-                let mut name = None;
-                let mut found_pub = current_is_pub;
-
-                // Check parent for pub
-                if let Some(parent) = node.parent() {
-                    for i in 0..parent.child_count() {
-                        let child = parent.child(i).unwrap();
-                        if child.kind() == "visibility_modifier" {
-                            found_pub = true;
-                        }
-                    }
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::capability_from_node;
+    use tree_sitter::Language;
 
-                // Extract function name
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "identifier" {
-                        name = Some(child.utf8_text(source.as_bytes())?.to_string());
-                        break;
-                    }
-                }
+    /// Toy extractor that claims `.rs` files and reports the root node only
+    struct WholeFileExtractor;
 
-                if found_pub && name.is_some() {
-                    let code_snippet = Self::extract_code_snippet(node, source);
-                    let line = node.start_position().row + 1;
-                    let authorship = self.get_authorship(path, line, line);
-                    
-                    capabilities.push(Capability {
-                        name: name.unwrap(),
-                        kind: CapabilityKind::Function,
-                        line,
-                        code_snippet,
-                        authorship,
-                    });
-                }
-            }
-            "struct_item" | "impl_item" => {
-                // Extract struct/impl names
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "type_identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        let code_snippet = Self::extract_code_snippet(node, source);
-                        let line = node.start_position().row + 1;
-                        let authorship = self.get_authorship(path, line, line);
-                        capabilities.push(Capability {
-                            name,
-                            kind: CapabilityKind::Class,
-                            line,
-                            code_snippet,
-                            authorship,
-                        });
-                        break;
-                    }
-                }
-            }
-            _ => {}
+    impl LanguageExtractor for WholeFileExtractor {
+        fn name(&self) -> &'static str {
+            "whole-file"
         }
 
-        // Recurse into children
-        for i in 0..node.child_count() {
-            if let Some(child) = node.child(i) {
-                self.traverse_rust_node(&child, source, capabilities, current_is_pub, path)?;
-            }
+        fn extensions(&self) -> &'static [&'static str] {
+            &["rs"]
         }
 
-        Ok(())
+        fn grammar(&self) -> Option<Language> {
+            Some(tree_sitter_rust::language())
+        }
+
+        fn extract(&self, file: &SourceFile) -> Result<Vec<Capability>> {
+            let root = file.root.expect("grammar was provided");
+            Ok(vec![capability_from_node(
+                "file".to_string(),
+                CapabilityKind::Class,
+                &root,
+                file.source,
+            )])
+        }
     }
 
-    /// Extract Python capabilities
-    fn extract_python_capabilities(
-        &self,
-        root: &tree_sitter::Node,
-        source: &str,
-        path: &Path,
-    ) -> Result<Vec<Capability>> {
-        let mut capabilities = Vec::new();
-        self.traverse_python_node(root, source, &mut capabilities, path)?;
-        Ok(capabilities)
+    #[test]
+    fn test_dispatches_by_extension() {
+        let mut shredder = Shredder::new().unwrap();
+
+        let caps = shredder
+            .shred_source(Path::new("lib.py"), "def handler():\n    pass\n")
+            .unwrap();
+        assert_eq!(caps.len(), 1);
+        assert_eq!(caps[0].name, "handler");
+        assert_eq!(caps[0].kind, CapabilityKind::Function);
+
+        let caps = shredder
+            .shred_source(Path::new("notes.txt"), "def handler(): pass")
+            .unwrap();
+        assert!(caps.is_empty());
     }
 
-    fn traverse_python_node(
-        &self,
-        node: &tree_sitter::Node,
-        source: &str,
-        capabilities: &mut Vec<Capability>,
-        path: &Path,
-    ) -> Result<()> {
-        let node_type = node.kind();
-
-        match node_type {
-            "function_definition" => {
-                // Extract function name
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        let code_snippet = Self::extract_code_snippet(node, source);
-                        let line = node.start_position().row + 1;
-                        let authorship = self.get_authorship(path, line, line);
-                        
-                        capabilities.push(Capability {
-                            name,
-                            kind: CapabilityKind::Function,
-                            line,
-                            code_snippet,
-                            authorship,
-                        });
-                        break;
-                    }
-                }
-            }
-            "class_definition" => {
-                // Extract class name
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        let code_snippet = Self::extract_code_snippet(node, source);
-                        let line = node.start_position().row + 1;
-                        let authorship = self.get_authorship(path, line, line);
-                        capabilities.push(Capability {
-                            name,
-                            kind: CapabilityKind::Class,
-                            line,
-                            code_snippet,
-                            authorship,
-                        });
-                        break;
-                    }
-                }
-            }
-            _ => {}
-        }
+    #[test]
+    fn test_parses_jsx_and_single_file_components() {
+        let mut shredder = Shredder::new().unwrap();
 
-        // Recurse
-        for i in 0..node.child_count() {
-            if let Some(child) = node.child(i) {
-                self.traverse_python_node(&child, source, capabilities, path)?;
-            }
+        let jsx = "export function Card({ title }) {\n  return <div className=\"card\">{title}</div>;\n}\n";
+        for file in ["Card.jsx", "Card.js", "Card.mjs"] {
+            let caps = shredder.shred_source(Path::new(file), jsx).unwrap();
+            let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+            assert_eq!(summary, vec![("Card", CapabilityKind::Component)], "{}", file);
         }
 
-        Ok(())
+        let tsx = "export const Badge = ({ n }: { n: number }) => <span>{n as number}</span>;\n";
+        let caps = shredder.shred_source(Path::new("Badge.tsx"), tsx).unwrap();
+        assert_eq!(caps[0].name, "Badge");
+
+        let vue = "<template>\n  <div>{{ total }}</div>\n</template>\n\n<script setup lang=\"ts\">\nexport function formatTotal(n: number): string {\n  return n.toFixed(2);\n}\n</script>\n";
+        let caps = shredder.shred_source(Path::new("order-summary.vue"), vue).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind, c.line)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("OrderSummary", CapabilityKind::Component, 1),
+                ("formatTotal", CapabilityKind::Function, 6),
+            ]
+        );
+        assert_eq!(caps[1].qualified_name, "OrderSummary.formatTotal");
+        assert_eq!(&vue[caps[1].byte_range.0..caps[1].byte_range.1], caps[1].code_snippet);
     }
 
-    /// Extract Go capabilities
-    fn extract_go_capabilities(
-        &self,
-        root: &tree_sitter::Node,
-        source: &str,
-        path: &Path,
-    ) -> Result<Vec<Capability>> {
-        let mut capabilities = Vec::new();
-        self.traverse_go_node(root, source, &mut capabilities, path)?;
-        Ok(capabilities)
+    #[test]
+    fn test_detects_framework_routes() {
+        let mut shredder = Shredder::new().unwrap();
+        let cases = [
+            ("server.ts", "app.post('/users', createUser);\n", "POST", "/users"),
+            ("main.py", "@app.get(\"/items/{id}\")\ndef read_item(id):\n    pass\n", "GET", "/items/{id}"),
+            ("main.go", "package main\n\nfunc main() {\n\tr.DELETE(\"/jobs/:id\", deleteJob)\n}\n", "DELETE", "/jobs/:id"),
+            ("main.rs", "fn app() -> Router {\n    Router::new().route(\"/health\", get(health))\n}\n", "GET", "/health"),
+            ("app/api/users/route.ts", "export async function PATCH(req: Request) {}\n", "PATCH", "/api/users"),
+        ];
+
+        for (file, source, method, path) in cases {
+            let caps = shredder.shred_source(Path::new(file), source).unwrap();
+            let route = caps
+                .iter()
+                .find(|c| c.kind == CapabilityKind::ApiRoute)
+                .and_then(|c| c.route.as_ref())
+                .unwrap_or_else(|| panic!("no route detected in {}", file));
+            assert_eq!((route.method.as_str(), route.path.as_str()), (method, path), "{}", file);
+        }
     }
 
-    fn traverse_go_node(
-        &self,
-        node: &tree_sitter::Node,
-        source: &str,
-        capabilities: &mut Vec<Capability>,
-        path: &Path,
-    ) -> Result<()> {
-        let node_type = node.kind();
-
-        match node_type {
-            "method_declaration" | "function_declaration" => {
-                // Extract function name
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        // Only export if it starts with uppercase (Go exports)
-                        if name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
-                            let code_snippet = Self::extract_code_snippet(node, source);
-                            let line = node.start_position().row + 1;
-                            let authorship = self.get_authorship(path, line, line);
-                            capabilities.push(Capability {
-                                name,
-                                kind: CapabilityKind::Function,
-                                line,
-                                code_snippet,
-                                authorship,
-                            });
-                        }
-                        break;
-                    }
-                }
-            }
-            "type_declaration" => {
-                // Extract type/struct names
-                for i in 0..node.child_count() {
-                    let child = node.child(i).unwrap();
-                    if child.kind() == "type_identifier" {
-                        let name = child.utf8_text(source.as_bytes())?.to_string();
-                        let code_snippet = Self::extract_code_snippet(node, source);
-                        let line = node.start_position().row + 1;
-                        let authorship = self.get_authorship(path, line, line);
-                        capabilities.push(Capability {
-                            name,
-                            kind: CapabilityKind::Class,
-                            line,
-                            code_snippet,
-                            authorship,
-                        });
-                        break;
-                    }
-                }
-            }
-            _ => {}
-        }
+    #[test]
+    fn test_queries_skip_nested_functions_and_impls() {
+        let mut shredder = Shredder::new().unwrap();
+
+        let source = "export const load = async () => {\n  const parse = (x) => x;\n  return items.map((i) => parse(i));\n};\n";
+        let caps = shredder.shred_source(Path::new("loader.ts"), source).unwrap();
+        let names: Vec<_> = caps.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["load"]);
+
+        let source = "pub struct Cache;\n\nimpl Cache {\n    pub fn get(&self) {}\n}\n";
+        let caps = shredder.shred_source(Path::new("cache.rs"), source).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![("Cache", CapabilityKind::Class), ("get", CapabilityKind::Function)]
+        );
+
+        // A class method named after an HTTP verb is no route
+        let source = "export class Cache {\n  constructor() {}\n  get(key) {\n    return this.items[key];\n  }\n}\n";
+        let caps = shredder.shred_source(Path::new("cache.ts"), source).unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.qualified_name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![("Cache", CapabilityKind::Class), ("Cache.get", CapabilityKind::Function)]
+        );
+        assert!(caps[1].route.is_none());
+    }
 
-        // Recurse
-        for i in 0..node.child_count() {
-            if let Some(child) = node.child(i) {
-                self.traverse_go_node(&child, source, capabilities, path)?;
-            }
+    #[test]
+    fn test_capability_metadata() {
+        let mut shredder = Shredder::new().unwrap();
+
+        let source = "impl Matchmaker {\n    /// Rank capabilities for a need\n    #[inline]\n    pub fn match_need(\n        &self,\n        need: &Need,\n    ) -> Vec<Match> {\n        vec![]\n    }\n}\n";
+        let caps = shredder.shred_source(Path::new("matchmaker.rs"), source).unwrap();
+        assert_eq!(caps.len(), 1);
+        let cap = &caps[0];
+        assert_eq!(cap.qualified_name, "Matchmaker::match_need");
+        assert_eq!(cap.signature, "pub fn match_need( &self, need: &Need, ) -> Vec<Match>");
+        assert_eq!(cap.doc_comment.as_deref(), Some("Rank capabilities for a need"));
+        assert_eq!(cap.visibility, Visibility::Public);
+        assert_eq!((cap.line, cap.end_line), (4, 9));
+        assert_eq!(&source[cap.byte_range.0..cap.byte_range.1], cap.code_snippet);
+
+        let source = "class Store:\n    def _load(self, key):\n        \"\"\"Read a key from disk.\"\"\"\n        return None\n";
+        let caps = shredder.shred_source(Path::new("store.py"), source).unwrap();
+        assert_eq!(caps[1].qualified_name, "Store._load");
+        assert_eq!(caps[1].signature, "def _load(self, key)");
+        assert_eq!(caps[1].doc_comment.as_deref(), Some("Read a key from disk."));
+        assert_eq!(caps[1].visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_detects_additional_kinds() {
+        let mut shredder = Shredder::new().unwrap();
+        type Expected = &'static [(&'static str, CapabilityKind)];
+        let cases: &[(&str, &str, Expected)] = &[
+            (
+                "cli.rs",
+                "#[derive(Parser)]\npub struct Cli;\n\npub trait Store {}\n\npub enum Mode { A }\n\npub mod api {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn parses() {}\n}\n",
+                &[
+                    ("Cli", CapabilityKind::CliCommand),
+                    ("Store", CapabilityKind::Interface),
+                    ("Mode", CapabilityKind::Enum),
+                    ("api", CapabilityKind::Module),
+                    ("parses", CapabilityKind::Test),
+                ],
+            ),
+            (
+                "models.py",
+                "class Color(Enum):\n    RED = 1\n\nclass Repo(Protocol):\n    pass\n\ndef test_color():\n    pass\n\ndef testing_mode(cfg):\n    pass\n\nclass TestRepo:\n    def test_get(self):\n        pass\n",
+                &[
+                    ("Color", CapabilityKind::Enum),
+                    ("Repo", CapabilityKind::Interface),
+                    ("test_color", CapabilityKind::Function), // Not in a test file
+                    ("testing_mode", CapabilityKind::Function),
+                    ("TestRepo", CapabilityKind::Class),
+                    ("test_get", CapabilityKind::Test),
+                ],
+            ),
+            (
+                "test_models.py",
+                "def test_color():\n    pass\n\ndef testing_mode(cfg):\n    pass\n",
+                &[("test_color", CapabilityKind::Test), ("testing_mode", CapabilityKind::Function)],
+            ),
+            (
+                "types.ts",
+                "export interface User {}\nexport enum Role { Admin }\nprogram.command('sync <dir>');\n",
+                &[
+                    ("User", CapabilityKind::Interface),
+                    ("Role", CapabilityKind::Enum),
+                    ("sync", CapabilityKind::CliCommand),
+                ],
+            ),
+        ];
+
+        for (file, source, expected) in cases {
+            let caps = shredder.shred_source(Path::new(file), source).unwrap();
+            let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+            assert_eq!(summary, expected.to_vec(), "{}", file);
         }
+    }
 
-        Ok(())
+    #[test]
+    fn test_custom_queries_extend_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("python.scm"),
+            "((call function: (identifier) @fn arguments: (argument_list (string) @name)) @api_route (#eq? @fn \"register\"))",
+        )
+        .unwrap();
+        let queries = CustomQueries::load_dir(dir.path(), &ExtractorRegistry::default()).unwrap();
+        let mut shredder = Shredder::new().unwrap().with_queries(queries);
+
+        let caps = shredder
+            .shred_source(Path::new("hooks.py"), "def setup():\n    register('on_save')\n")
+            .unwrap();
+        let summary: Vec<_> = caps.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![("setup", CapabilityKind::Function), ("on_save", CapabilityKind::ApiRoute)]
+        );
     }
 
-    /// Get authorship info for a line range in a file
-    fn get_authorship(&self, path: &Path, start_line: usize, end_line: usize) -> Option<AuthorshipInfo> {
-        if let Some(ref analyzer) = self.authorship_analyzer {
-            analyzer.analyze_file(path, start_line, end_line).ok()
-        } else {
-            None
-        }
+    #[test]
+    fn test_registered_extractor_overrides_builtin() {
+        let mut shredder = Shredder::new().unwrap();
+        shredder.register_extractor(Box::new(WholeFileExtractor));
+
+        let caps = shredder
+            .shred_source(Path::new("lib.rs"), "pub fn run() {}\n")
+            .unwrap();
+        assert_eq!(caps.len(), 1);
+        assert_eq!(caps[0].name, "file");
     }
 }
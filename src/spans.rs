@@ -0,0 +1,71 @@
+//! Spans of configuration and schema files (YAML, JSON, HCL, GraphQL), for
+//! capabilities built without a grammar: contracts and infrastructure.
+
+/// Byte offset of the first mapping key `key` (`key:`, `"key":`) at the start
+/// of a line at or after `from`
+pub fn key_offset(source: &str, key: &str, from: usize) -> Option<usize> {
+    let mut offset = from;
+    for line in source[from..].split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let unquoted = trimmed.trim_start_matches(['"', '\'']);
+        if let Some(rest) = unquoted.strip_prefix(key) {
+            if rest.trim_start_matches(['"', '\'']).trim_start().starts_with(':') {
+                return Some(offset + line.len() - trimmed.len());
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// End of the block whose key starts at `start`: its last line before the
+/// next line indented no deeper. YAML list items and a closing JSON bracket
+/// at the key's own depth still belong to the block.
+pub fn indented_block_end(source: &str, start: usize) -> usize {
+    let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let indent = start - line_start;
+    let first_line_end = source[start..].find('\n').map(|i| start + i).unwrap_or(source.len());
+
+    let mut end = first_line_end;
+    let mut offset = first_line_end;
+    for line in source[first_line_end..].split_inclusive('\n') {
+        let content = line.trim_end();
+        let trimmed = content.trim_start();
+        if !trimmed.is_empty() {
+            let depth = content.len() - trimmed.len();
+            if depth < indent || (depth == indent && !trimmed.starts_with(['-', '}', ']'])) {
+                break;
+            }
+            end = offset + content.len();
+            if depth == indent && !trimmed.starts_with('-') {
+                break; // Closing bracket
+            }
+        }
+        offset += line.len();
+    }
+    end
+}
+
+/// Doc comment of a generated capability (contracts, infrastructure): the
+/// generated description, followed by the author's own comment if any
+pub fn describe(generated: String, comment: Option<String>) -> String {
+    match comment {
+        Some(comment) => format!("{}\n{}", generated, comment),
+        None => generated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_indented_blocks() {
+        let source = "jobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      - run: make\n  \"deploy\":\n    needs: build\n";
+        let build = key_offset(source, "build", 0).unwrap();
+        assert_eq!(&source[build..indented_block_end(source, build)], "build:\n    runs-on: ubuntu-latest\n    steps:\n      - run: make");
+        let deploy = key_offset(source, "deploy", build).unwrap();
+        assert_eq!(&source[deploy..indented_block_end(source, deploy)], "\"deploy\":\n    needs: build");
+        assert_eq!(describe("Runs CI".to_string(), Some("Nightly".to_string())), "Runs CI\nNightly");
+    }
+}
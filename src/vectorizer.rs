@@ -1,8 +1,13 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use serde_json;
+use std::collections::HashMap;
 use std::time::Duration;
 
+use crate::authorship::Contributor;
+use crate::cache::EmbeddingCache;
+use crate::metrics::CapabilityMetrics;
+use crate::shredder::{Capability, TestEvidence};
+
 /// THE VECTORIZER: Generates embeddings using Ollama
 #[derive(Clone)]
 pub struct Vectorizer {
@@ -24,7 +29,7 @@ struct EmbeddingResponse {
     embedding: Vec<f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EmbeddedCapability {
     pub name: String,
     pub code_snippet: String,
@@ -46,6 +51,95 @@ pub struct EmbeddedCapability {
     pub is_self_authored: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contribution_percentage: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contributors: Option<Vec<Contributor>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_commit_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_commit_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_last_touched_at: Option<String>,
+    // Declaration metadata (optional for backward compatibility)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualified_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_range: Option<(usize, usize)>,
+    // Notebook cell index; `line` is then within the cell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell: Option<usize>,
+    // Workspace package containing the capability (see workspace.rs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_root: Option<String>,
+    // HTTP route fields (only set for api_route capabilities)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_path: Option<String>,
+    // Tests exercising this capability
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<TestEvidence>,
+    // Dependency graph fields (see graph.rs)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<usize>,
+    // Size and complexity metrics (see metrics.rs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<CapabilityMetrics>,
+}
+
+impl EmbeddedCapability {
+    /// Build an (unembedded) capability record from a shredded capability
+    pub fn from_capability(cap: &Capability, language: &str, path: &str) -> Self {
+        let mut embedded = Self {
+            name: cap.name.clone(),
+            code_snippet: cap.code_snippet.clone(),
+            language: language.to_string(),
+            kind: cap.kind.as_str().to_string(),
+            path: path.to_string(),
+            line: cap.line,
+            qualified_name: Some(cap.qualified_name.clone()),
+            signature: Some(cap.signature.clone()),
+            doc_comment: cap.doc_comment.clone(),
+            visibility: Some(cap.visibility.as_str().to_string()),
+            end_line: Some(cap.end_line),
+            byte_range: Some(cap.byte_range),
+            cell: cap.cell,
+            tests: cap.tests.clone(),
+            metrics: Some(cap.metrics.clone()),
+            ..Default::default()
+        };
+        if let Some(auth) = &cap.authorship {
+            embedded.author_email = auth.author_email.clone();
+            embedded.author_name = auth.author_name.clone();
+            embedded.commit_sha = auth.commit_sha.clone();
+            embedded.authorship_confidence = Some(auth.authorship_confidence);
+            embedded.is_self_authored = Some(auth.is_self_authored);
+            embedded.contribution_percentage = Some(auth.contribution_percentage);
+            embedded.contributors = Some(auth.contributors.clone());
+            embedded.first_commit_at = auth.first_commit_at.clone();
+            embedded.last_commit_at = auth.last_commit_at.clone();
+            embedded.commit_count = Some(auth.commit_count);
+            embedded.user_last_touched_at = auth.user_last_touched_at.clone();
+        }
+        if let Some(route) = &cap.route {
+            embedded.http_method = Some(route.method.clone());
+            embedded.route_path = Some(route.path.clone());
+        }
+        embedded
+    }
 }
 
 impl Vectorizer {
@@ -129,12 +223,7 @@ impl Vectorizer {
                 kind: kind.to_string(),
                 path: path.to_string(),
                 line,
-                author_email: None,
-                author_name: None,
-                commit_sha: None,
-                authorship_confidence: None,
-                is_self_authored: None,
-                contribution_percentage: None,
+                ..Default::default()
             });
         }
 
@@ -148,16 +237,12 @@ impl Vectorizer {
             kind: kind.to_string(),
             path: path.to_string(),
             line,
-            author_email: None,
-            author_name: None,
-            commit_sha: None,
-            authorship_confidence: None,
-            is_self_authored: None,
-            contribution_percentage: None,
+            ..Default::default()
         })
     }
 
     /// Helper function to embed a single capability or need
+    #[allow(clippy::too_many_arguments)]
     pub async fn embed_single(
         client: reqwest::Client,
         url: String,
@@ -169,10 +254,7 @@ impl Vectorizer {
         path: String,
         line: usize,
     ) -> Result<EmbeddedCapability> {
-        let prompt = format!(
-            "Capability: {}\nLanguage: {}\nType: {}\nContent:\n{}",
-            name, lang, kind, content
-        );
+        let prompt = Self::prompt(&name, &lang, &kind, &content);
 
         let request = serde_json::json!({
             "model": model,
@@ -208,12 +290,7 @@ impl Vectorizer {
                             kind,
                             path,
                             line,
-                            author_email: None,
-                            author_name: None,
-                            commit_sha: None,
-                            authorship_confidence: None,
-                            is_self_authored: None,
-                            contribution_percentage: None,
+                            ..Default::default()
                         })
                     }
                     Err(_) => {
@@ -225,12 +302,7 @@ impl Vectorizer {
                             kind,
                             path,
                             line,
-                            author_email: None,
-                            author_name: None,
-                            commit_sha: None,
-                            authorship_confidence: None,
-                            is_self_authored: None,
-                            contribution_percentage: None,
+                            ..Default::default()
                         })
                     }
                 }
@@ -244,22 +316,69 @@ impl Vectorizer {
                     kind,
                     path,
                     line,
-                    author_email: None,
-                    author_name: None,
-                    commit_sha: None,
-                    authorship_confidence: None,
-                    is_self_authored: None,
-                    contribution_percentage: None,
+                    ..Default::default()
                 })
             }
         }
     }
 
-    /// Batch embed capabilities (for efficiency)
-    /// Tuple format: (name, code, lang, kind, path, line, authorship_info)
+    /// What `embed_single` embeds for a capability or need
+    fn prompt(name: &str, lang: &str, kind: &str, content: &str) -> String {
+        format!(
+            "Capability: {}\nLanguage: {}\nType: {}\nContent:\n{}",
+            name, lang, kind, content
+        )
+    }
+
+    /// The content embedded for a capability. The doc comment describes
+    /// intent better than the code alone.
+    fn content(capability: &EmbeddedCapability) -> String {
+        match &capability.doc_comment {
+            Some(doc) => format!("{}\n{}", doc, capability.code_snippet),
+            None => capability.code_snippet.clone(),
+        }
+    }
+
+    /// `embed_capabilities`, reusing the embedding in `cache` of any capability
+    /// whose prompt was embedded before. Also returns how many were reused.
+    pub async fn embed_capabilities_cached(
+        &self,
+        mut capabilities: Vec<EmbeddedCapability>,
+        cache: &mut EmbeddingCache,
+    ) -> Result<(Vec<EmbeddedCapability>, usize)> {
+        let prompts: Vec<String> = capabilities
+            .iter()
+            .map(|cap| Self::prompt(&cap.name, &cap.language, &cap.kind, &Self::content(cap)))
+            .collect();
+        let mut pending = Vec::new();
+        for (capability, prompt) in capabilities.iter_mut().zip(&prompts) {
+            match cache.get(&self.model, prompt) {
+                Some(embedding) => capability.embedding = embedding,
+                None => pending.push(capability.clone()),
+            }
+        }
+        let reused = capabilities.len() - pending.len();
+
+        let mut fresh = HashMap::new();
+        for embedded in self.embed_capabilities(pending).await? {
+            let prompt = Self::prompt(&embedded.name, &embedded.language, &embedded.kind, &Self::content(&embedded));
+            if !embedded.embedding.is_empty() {
+                cache.set(&self.model, &prompt, embedded.embedding.clone());
+            }
+            fresh.insert(prompt, embedded.embedding);
+        }
+        for (capability, prompt) in capabilities.iter_mut().zip(&prompts) {
+            if capability.embedding.is_empty() {
+                capability.embedding = fresh.get(prompt).cloned().unwrap_or_default();
+            }
+        }
+        Ok((capabilities, reused))
+    }
+
+    /// Batch embed capabilities (for efficiency), filling in each record's embedding
     pub async fn embed_capabilities(
         &self,
-        capabilities: Vec<(String, String, String, String, String, usize, Option<crate::authorship::AuthorshipInfo>)>,
+        capabilities: Vec<EmbeddedCapability>,
     ) -> Result<Vec<EmbeddedCapability>> {
         let mut embedded = Vec::new();
 
@@ -271,34 +390,26 @@ impl Vectorizer {
         
         let futures: Vec<_> = capabilities
             .into_iter()
-            .map(|(name, code, lang, kind, path, line, authorship)| {
+            .map(|mut capability| {
                 let client_clone = client.clone();
                 let url_clone = url.clone();
                 let model_clone = model.clone();
                 async move {
-                    let mut embedded = Self::embed_single(
+                    let content = Self::content(&capability);
+                    let result = Self::embed_single(
                         client_clone,
                         url_clone,
                         model_clone,
-                        name.clone(),
-                        code,
-                        lang,
-                        kind,
-                        path,
-                        line,
+                        capability.name.clone(),
+                        content,
+                        capability.language.clone(),
+                        capability.kind.clone(),
+                        capability.path.clone(),
+                        capability.line,
                     ).await?;
+                    capability.embedding = result.embedding;
                     
-                    // Add authorship info if available
-                    if let Some(auth) = authorship {
-                        embedded.author_email = auth.author_email;
-                        embedded.author_name = auth.author_name;
-                        embedded.commit_sha = auth.commit_sha;
-                        embedded.authorship_confidence = Some(auth.authorship_confidence);
-                        embedded.is_self_authored = Some(auth.is_self_authored);
-                        embedded.contribution_percentage = Some(auth.contribution_percentage);
-                    }
-                    
-                    Ok::<EmbeddedCapability, anyhow::Error>(embedded)
+                    Ok::<EmbeddedCapability, anyhow::Error>(capability)
                 }
             })
             .collect();
@@ -0,0 +1,151 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::vectorizer::EmbeddedCapability;
+
+/// Manifests that can name a package
+const MANIFESTS: &[&str] = &["Cargo.toml", "package.json", "go.mod", "pyproject.toml"];
+
+/// A package inside the scanned tree
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Package {
+    pub name: String,
+    pub root: PathBuf,
+    pub ecosystem: &'static str, // "rust", "javascript", "go" or "python", as in manifest.rs
+}
+
+/// THE WORKSPACE: Package boundaries of a scanned tree, so capabilities of a
+/// monorepo can be grouped per package.
+///
+/// Every manifest that names a package (`Cargo.toml` with a `[package]`,
+/// `package.json`, `go.mod`, `pyproject.toml`) starts a package at its
+/// directory. Members of Cargo, npm/pnpm and Go workspaces are found through
+/// their own manifests; a workspace root without a package of its own
+/// (a virtual `Cargo.toml`) is not one.
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    packages: Vec<Package>,
+}
+
+impl Workspace {
+    /// Packages declared by the manifests among `files`
+    pub fn discover(files: &[PathBuf]) -> Self {
+        let mut packages: Vec<Package> = files
+            .iter()
+            .filter(|file| {
+                let file_name = file.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                MANIFESTS.contains(&file_name)
+            })
+            .filter_map(|file| {
+                let source = fs::read_to_string(file).ok()?;
+                let (name, ecosystem) = package_name(file, &source)?;
+                Some(Package {
+                    name,
+                    root: file.parent()?.to_path_buf(),
+                    ecosystem,
+                })
+            })
+            .collect();
+        // Deepest roots first, so the innermost package wins
+        packages.sort_by(|a, b| {
+            b.root
+                .components()
+                .count()
+                .cmp(&a.root.components().count())
+                .then_with(|| a.root.cmp(&b.root))
+        });
+        packages.dedup_by(|a, b| a.root == b.root); // `package.json` next to `pyproject.toml`
+        Workspace { packages }
+    }
+
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    /// The innermost package containing `path`
+    pub fn package_for(&self, path: &Path) -> Option<&Package> {
+        self.packages.iter().find(|p| path.starts_with(&p.root))
+    }
+
+    /// Tag each capability with its package name and root
+    pub fn annotate(&self, capabilities: &mut [EmbeddedCapability]) {
+        for cap in capabilities.iter_mut() {
+            if let Some(package) = self.package_for(Path::new(&cap.path)) {
+                cap.package_name = Some(package.name.clone());
+                cap.package_root = Some(package.root.display().to_string());
+            }
+        }
+    }
+}
+
+/// Name and ecosystem of the package a manifest declares, if it declares one
+pub fn package_name(manifest: &Path, source: &str) -> Option<(String, &'static str)> {
+    let file_name = manifest.file_name()?.to_str()?;
+    let (name, ecosystem) = match file_name {
+        "Cargo.toml" => {
+            let manifest: toml::Table = toml::from_str(source).ok()?;
+            (manifest.get("package")?.get("name")?.as_str()?.to_string(), "rust")
+        }
+        "package.json" => {
+            let manifest: serde_json::Value = serde_json::from_str(source).ok()?;
+            (manifest.get("name")?.as_str()?.to_string(), "javascript")
+        }
+        "go.mod" => {
+            let module = source.lines().find_map(|line| line.trim().strip_prefix("module "))?;
+            (module.trim().trim_matches('"').to_string(), "go")
+        }
+        "pyproject.toml" => {
+            let manifest: toml::Table = toml::from_str(source).ok()?;
+            let name = manifest
+                .get("project")
+                .and_then(|p| p.get("name"))
+                .or_else(|| manifest.get("tool")?.get("poetry")?.get("name"))?;
+            (name.as_str()?.to_string(), "python")
+        }
+        _ => return None,
+    };
+    (!name.is_empty()).then_some((name, ecosystem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groups_files_by_innermost_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let files = [
+            ("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n"),
+            ("crates/core/Cargo.toml", "[package]\nname = \"shop-core\"\nversion = \"0.1.0\"\n"),
+            ("web/package.json", "{ \"name\": \"@shop/web\", \"private\": true }"),
+            ("web/tools/go.mod", "module github.com/shop/tools\n\ngo 1.21\n"),
+            ("ml/pyproject.toml", "[tool.poetry]\nname = \"shop-ml\"\n"),
+        ];
+        let mut paths = Vec::new();
+        for (path, source) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, source).unwrap();
+            paths.push(path);
+        }
+
+        let workspace = Workspace::discover(&paths);
+        assert_eq!(workspace.packages().len(), 4);
+        let package = |file: &str| workspace.package_for(&root.join(file)).map(|p| (p.name.as_str(), p.ecosystem));
+        assert_eq!(package("crates/core/src/lib.rs"), Some(("shop-core", "rust")));
+        assert_eq!(package("web/src/App.tsx"), Some(("@shop/web", "javascript")));
+        assert_eq!(package("web/tools/main.go"), Some(("github.com/shop/tools", "go")));
+        assert_eq!(package("ml/train.py"), Some(("shop-ml", "python")));
+        assert_eq!(package("scripts/release.sh"), None);
+
+        let mut caps = vec![EmbeddedCapability {
+            path: root.join("web/src/App.tsx").display().to_string(),
+            ..Default::default()
+        }];
+        workspace.annotate(&mut caps);
+        assert_eq!(caps[0].package_name.as_deref(), Some("@shop/web"));
+        assert_eq!(caps[0].package_root, Some(root.join("web").display().to_string()));
+    }
+}